@@ -6,8 +6,6 @@ const rotBtn = document.getElementById('rotBtn');
 const shineBtn = document.getElementById('shineBtn');
 const bwBtn = document.getElementById('bwBtn');
 
-const saturation = 0.75;
-
 const scene = new THREE.Scene();
 let camera = new THREE.PerspectiveCamera(80, window.innerWidth / window.innerHeight, 0.1, 1000);
 camera.position.set(25, 2.5, 25);
@@ -36,6 +34,8 @@ let isRotate = true;
 let isShiny = false;
 let isBW = true;
 let isLogHeight = false;
+// the evaluated grid, kept so the style toggles only re-derive heights and colours
+let samples = null;
 
 let resolution = new THREE.Vector2(window.innerWidth, window.innerHeight);
 let graph = new THREE.Object3D();
@@ -45,9 +45,17 @@ scene.add(graph);
 
 createGrid();
 createText();
-window.init().then(() => {
-    load();
-});
+window
+    .init()
+    .then(() => {
+        // only a build with the parallel feature exports initThreadPool, and
+        // its web workers need SharedArrayBuffer (a cross-origin isolated page)
+        if (window.initThreadPool && window.crossOriginIsolated)
+            return window.initThreadPool(navigator.hardwareConcurrency);
+    })
+    .then(() => {
+        load();
+    });
 render();
 onWindowResize();
 window.addEventListener('resize', onWindowResize);
@@ -91,10 +99,6 @@ function createGrid() {
 
 function createMesh() {
     return new Promise(function (resolve, reject) {
-        let width = 2 * length; // 20
-        let height = width;
-        let segments = multiplier * width;
-        let plane = new THREE.PlaneBufferGeometry(width, height, segments, segments);
         let legend = document.getElementById('legend');
         if (plot == 'Re-Im') {
             legend.innerHTML =
@@ -103,7 +107,10 @@ function createMesh() {
             legend.innerHTML =
                 'height of surface = Re(f(z))<br>color of surface - brighter = bigger Re(f(z)), darker = smaller Re(f(z))';
         } else {
-            if (isBW) toggleBW();
+            if (isBW) {
+                bwBtn.innerText = 'Switch to B/W';
+                isBW = false;
+            }
             legend.innerHTML = 'height of surface = modulus of output<br>color of surface - argument of output (R→G→B)';
         }
         let graph_type = 4;
@@ -114,30 +121,18 @@ function createMesh() {
             if (isBW) graph_type = 3;
             else graph_type = 2;
         }
-        let res = window.evaluate(inputBox.value, multiplier, graph_type, isLogHeight);
-
-        let posLen = 3 * Math.pow(20 * multiplier + 1, 2);
-        let pos = res.slice(0, posLen);
-        let colors = res.slice(posLen);
-
-        // color logging
-        // for (let i = 0; i < colors.length; i += 3) {
-        //     let r = parseInt(colors[i] * 255);
-        //     let g = parseInt(colors[i + 1] * 255);
-        //     let b = parseInt(colors[i + 2] * 255);
-        //     let R = r.toString(16);
-        //     let G = g.toString(16);
-        //     let B = b.toString(16);
-        //     console.log('%c#' + R + G + B, `color:#${R + G + B}`);
-        // }
-        plane.attributes.position.array = pos;
-        plane.setAttribute('color', new THREE.BufferAttribute(new Float32Array(colors), 3));
+        // the colours evaluate() uses for this graph type
+        let colormap = window.default_colormap(graph_type);
+        let saturation = window.default_saturation(graph_type);
+        let heightMap = isLogHeight ? 'log' : 'linear';
+        let res = samples.render(graph_type, heightMap, colormap, saturation, 1, 1, '');
+        let geometry = meshGeometry(res);
+        res.free();
 
-        plane.computeVertexNormals();
         let mesh;
         if (isShiny)
             mesh = new THREE.Mesh(
-                plane,
+                geometry,
                 new THREE.MeshStandardMaterial({
                     vertexColors: THREE.VertexColors,
                     side: THREE.DoubleSide,
@@ -148,7 +143,7 @@ function createMesh() {
             );
         else
             mesh = new THREE.Mesh(
-                plane,
+                geometry,
                 new THREE.MeshLambertMaterial({
                     vertexColors: THREE.VertexColors,
                     side: THREE.DoubleSide
@@ -160,6 +155,17 @@ function createMesh() {
     });
 }
 
+// the PlotResult's arrays are views into wasm memory, so they are copied
+// (slice) before anything else calls into wasm
+function meshGeometry(res) {
+    let geometry = new THREE.BufferGeometry();
+    geometry.setAttribute('position', new THREE.BufferAttribute(res.positions.slice(), 3));
+    geometry.setAttribute('color', new THREE.BufferAttribute(res.colors.slice(), res.colorChannels));
+    geometry.setAttribute('normal', new THREE.BufferAttribute(res.normals.slice(), 3));
+    geometry.setIndex(new THREE.BufferAttribute(res.indices.slice(), 1));
+    return geometry;
+}
+
 function createText() {
     const loader = new THREE.FontLoader();
 
@@ -199,6 +205,13 @@ function createText() {
 
 function load() {
     multiplier = document.getElementById('gpuLevel').value;
+    if (samples) samples.free();
+    samples = new window.Plot(inputBox.value, multiplier);
+    restyle();
+}
+
+// rebuilds the mesh from the cached samples without evaluating f again
+function restyle() {
     plot = plotChooser.value;
     clearMesh();
     createMesh().then((mesh) => {
@@ -252,7 +265,7 @@ function exportImg() {
     container.appendChild(renderer.domElement);
     controls = new THREE.OrbitControls(camera, renderer.domElement);
 
-    load();
+    restyle();
     renderer.render(scene, camera);
     // image stuff
     let imgData = document.getElementsByTagName('canvas')[0].toDataURL('image/png');
@@ -278,18 +291,18 @@ function toggleShiny() {
     if (isShiny) shineBtn.innerText = 'Shine Off';
     else shineBtn.innerText = 'Shine On';
     isShiny = !isShiny;
-    load();
+    restyle();
 }
 function toggleBW() {
     if (isBW) bwBtn.innerText = 'Switch to B/W';
     else bwBtn.innerText = 'Switch to color';
     isBW = !isBW;
-    load();
+    restyle();
 }
 
 function toggleLog() {
     isLogHeight = !isLogHeight;
-    load();
+    restyle();
 }
 function toggleOrth() {
     isPerspective = !isPerspective;
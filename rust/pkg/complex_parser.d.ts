@@ -1,31 +1,248 @@
 /* tslint:disable */
 /* eslint-disable */
-/**
-* @param {string} input
-* @param {number} n
-* @param {number} graph_type
-* @param {boolean} log_height
-* @returns {Float32Array}
-*/
+
+export class Evaluator {
+    free(): void;
+    [Symbol.dispose](): void;
+    cancel(): void;
+    finish(): Plot;
+    constructor(input: string, n: number, coarse_to_fine: boolean);
+    static onDomain(input: string, n: number, domain: string, coarse_to_fine: boolean): Evaluator;
+    preview(): Plot;
+    progress(): number;
+    step(max_points: number): boolean;
+    readonly cancelled: boolean;
+    readonly done: boolean;
+    readonly stride: number;
+}
+
+export class Plot {
+    free(): void;
+    [Symbol.dispose](): void;
+    contoursOnSurface(quantity: string, levels: Float32Array, graph_type: number, height_map: string): Polylines;
+    contours(quantity: string, levels: Float32Array): Polylines;
+    constructor(input: string, n: number);
+    static onDomain(input: string, n: number, domain: string): Plot;
+    render(graph_type: number, height_map: string, colormap: string, saturation: number, value: number, opacity: number, alpha: string): PlotResult;
+    stats(): PlotStats;
+    readonly cols: number;
+    readonly n: number;
+    readonly rows: number;
+}
+
+export class PlotResult {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    readonly colorChannels: number;
+    readonly cols: number;
+    readonly heightMax: number;
+    readonly heightMin: number;
+    readonly imMax: number;
+    readonly imMin: number;
+    readonly reMax: number;
+    readonly reMin: number;
+    readonly rows: number;
+    readonly colors: Float32Array;
+    readonly indices: Uint32Array;
+    readonly mask: Uint8Array;
+    readonly normals: Float32Array;
+    readonly positions: Float32Array;
+    readonly values: Float32Array;
+    readonly vertexCount: number;
+}
+
+export class PlotStats {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    readonly im: Summary;
+    readonly infinite: number;
+    readonly nan: number;
+    readonly norm: Summary;
+    readonly re: Summary;
+    readonly total: number;
+    readonly nonFinite: number;
+}
+
+export class Polylines {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    toSvg(stroke: string, stroke_width: number): string;
+    readonly lineCount: number;
+    readonly offsets: Uint32Array;
+    readonly points: Float32Array;
+}
+
+export class Summary {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    readonly count: number;
+    readonly max: number;
+    readonly mean: number;
+    readonly median: number;
+    readonly min: number;
+    readonly p1: number;
+    readonly p5: number;
+    readonly p95: number;
+    readonly p99: number;
+}
+
+export class SvgExport {
+    free(): void;
+    [Symbol.dispose](): void;
+    addLines(lines: Polylines, stroke: string, stroke_width: number): void;
+    constructor(plot: Plot, graph_type: number, colormap: string, saturation: number, value: number);
+    toSvg(size: number): string;
+    axes: boolean;
+    colorbar: boolean;
+    markers: boolean;
+}
+
+export class VectorField {
+    free(): void;
+    [Symbol.dispose](): void;
+    constructor(input: string, spacing: number, seeds: number, step: number, max_steps: number);
+    onSurface(plot: Plot, graph_type: number, height_map: string): VectorField;
+    readonly glyphCount: number;
+    readonly glyphs: Float32Array;
+    readonly streamlines: Polylines;
+}
+
+export enum VertexKind {
+    Undefined = 0,
+    Finite = 1,
+    Pole = 2,
+}
+
+export function colormap_names(): any[];
+
+export function conformal_grid(input: string, curves: string, tolerance: number, split: boolean): Polylines;
+
+export function default_colormap(graph_type: number): string;
+
+export function default_saturation(graph_type: number): number;
+
 export function evaluate(input: string, n: number, graph_type: number, log_height: boolean): Float32Array;
 
+export function evaluate_styled(input: string, n: number, graph_type: number, height_map: string, colormap: string, saturation: number, value: number, opacity: number, alpha: string): PlotResult;
+
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
-  readonly memory: WebAssembly.Memory;
-  readonly evaluate: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
-  readonly __wbindgen_add_to_stack_pointer: (a: number) => number;
-  readonly __wbindgen_malloc: (a: number) => number;
-  readonly __wbindgen_realloc: (a: number, b: number, c: number) => number;
-  readonly __wbindgen_free: (a: number, b: number) => void;
+    readonly memory: WebAssembly.Memory;
+    readonly __wbg_evaluator_free: (a: number, b: number) => void;
+    readonly __wbg_get_plot_cols: (a: number) => number;
+    readonly __wbg_get_plot_n: (a: number) => number;
+    readonly __wbg_get_plot_rows: (a: number) => number;
+    readonly __wbg_get_plotresult_colorChannels: (a: number) => number;
+    readonly __wbg_get_plotresult_cols: (a: number) => number;
+    readonly __wbg_get_plotresult_heightMax: (a: number) => number;
+    readonly __wbg_get_plotresult_heightMin: (a: number) => number;
+    readonly __wbg_get_plotresult_imMax: (a: number) => number;
+    readonly __wbg_get_plotresult_imMin: (a: number) => number;
+    readonly __wbg_get_plotresult_reMax: (a: number) => number;
+    readonly __wbg_get_plotresult_reMin: (a: number) => number;
+    readonly __wbg_get_plotresult_rows: (a: number) => number;
+    readonly __wbg_get_plotstats_im: (a: number) => number;
+    readonly __wbg_get_plotstats_infinite: (a: number) => number;
+    readonly __wbg_get_plotstats_nan: (a: number) => number;
+    readonly __wbg_get_plotstats_norm: (a: number) => number;
+    readonly __wbg_get_plotstats_re: (a: number) => number;
+    readonly __wbg_get_plotstats_total: (a: number) => number;
+    readonly __wbg_get_summary_count: (a: number) => number;
+    readonly __wbg_get_summary_max: (a: number) => number;
+    readonly __wbg_get_summary_mean: (a: number) => number;
+    readonly __wbg_get_summary_median: (a: number) => number;
+    readonly __wbg_get_summary_min: (a: number) => number;
+    readonly __wbg_get_summary_p1: (a: number) => number;
+    readonly __wbg_get_summary_p5: (a: number) => number;
+    readonly __wbg_get_summary_p95: (a: number) => number;
+    readonly __wbg_get_summary_p99: (a: number) => number;
+    readonly __wbg_get_svgexport_axes: (a: number) => number;
+    readonly __wbg_get_svgexport_colorbar: (a: number) => number;
+    readonly __wbg_get_svgexport_markers: (a: number) => number;
+    readonly __wbg_plot_free: (a: number, b: number) => void;
+    readonly __wbg_plotresult_free: (a: number, b: number) => void;
+    readonly __wbg_plotstats_free: (a: number, b: number) => void;
+    readonly __wbg_polylines_free: (a: number, b: number) => void;
+    readonly __wbg_set_svgexport_axes: (a: number, b: number) => void;
+    readonly __wbg_set_svgexport_colorbar: (a: number, b: number) => void;
+    readonly __wbg_set_svgexport_markers: (a: number, b: number) => void;
+    readonly __wbg_summary_free: (a: number, b: number) => void;
+    readonly __wbg_svgexport_free: (a: number, b: number) => void;
+    readonly __wbg_vectorfield_free: (a: number, b: number) => void;
+    readonly colormap_names: () => [number, number];
+    readonly conformal_grid: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+    readonly default_colormap: (a: number) => [number, number];
+    readonly default_saturation: (a: number) => number;
+    readonly evaluate: (a: number, b: number, c: number, d: number, e: number) => [number, number, number, number];
+    readonly evaluate_styled: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number) => [number, number, number];
+    readonly evaluator_cancel: (a: number) => void;
+    readonly evaluator_cancelled: (a: number) => number;
+    readonly evaluator_done: (a: number) => number;
+    readonly evaluator_finish: (a: number) => [number, number, number];
+    readonly evaluator_new: (a: number, b: number, c: number, d: number) => [number, number, number];
+    readonly evaluator_onDomain: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+    readonly evaluator_preview: (a: number) => number;
+    readonly evaluator_progress: (a: number) => number;
+    readonly evaluator_step: (a: number, b: number) => [number, number, number];
+    readonly evaluator_stride: (a: number) => number;
+    readonly plot_contours: (a: number, b: number, c: number, d: number, e: number) => [number, number, number];
+    readonly plot_contoursOnSurface: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => [number, number, number];
+    readonly plot_new: (a: number, b: number, c: number) => [number, number, number];
+    readonly plot_onDomain: (a: number, b: number, c: number, d: number, e: number) => [number, number, number];
+    readonly plot_render: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number) => [number, number, number];
+    readonly plot_stats: (a: number) => number;
+    readonly plotresult_colors: (a: number) => any;
+    readonly plotresult_indices: (a: number) => any;
+    readonly plotresult_mask: (a: number) => any;
+    readonly plotresult_normals: (a: number) => any;
+    readonly plotresult_positions: (a: number) => any;
+    readonly plotresult_values: (a: number) => any;
+    readonly plotresult_vertexCount: (a: number) => number;
+    readonly plotstats_nonFinite: (a: number) => number;
+    readonly polylines_lineCount: (a: number) => number;
+    readonly polylines_offsets: (a: number) => any;
+    readonly polylines_points: (a: number) => any;
+    readonly polylines_toSvg: (a: number, b: number, c: number, d: number) => [number, number];
+    readonly svgexport_addLines: (a: number, b: number, c: number, d: number, e: number) => void;
+    readonly svgexport_new: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+    readonly svgexport_toSvg: (a: number, b: number) => [number, number];
+    readonly vectorfield_glyphCount: (a: number) => number;
+    readonly vectorfield_glyphs: (a: number) => any;
+    readonly vectorfield_new: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+    readonly vectorfield_onSurface: (a: number, b: number, c: number, d: number, e: number) => [number, number, number];
+    readonly vectorfield_streamlines: (a: number) => number;
+    readonly __wbindgen_externrefs: WebAssembly.Table;
+    readonly __externref_drop_slice: (a: number, b: number) => void;
+    readonly __wbindgen_free: (a: number, b: number, c: number) => void;
+    readonly __wbindgen_malloc: (a: number, b: number) => number;
+    readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
+    readonly __externref_table_dealloc: (a: number) => void;
+    readonly __wbindgen_start: () => void;
 }
 
+export type SyncInitInput = BufferSource | WebAssembly.Module;
+
+/**
+ * Instantiates the given `module`, which can either be bytes or
+ * a precompiled `WebAssembly.Module`.
+ *
+ * @param {{ module: SyncInitInput }} module - Passing `SyncInitInput` directly is deprecated.
+ *
+ * @returns {InitOutput}
+ */
+export function initSync(module: { module: SyncInitInput } | SyncInitInput): InitOutput;
+
 /**
-* If `module_or_path` is {RequestInfo} or {URL}, makes a request and
-* for everything else, calls `WebAssembly.instantiate` directly.
-*
-* @param {InitInput | Promise<InitInput>} module_or_path
-*
-* @returns {Promise<InitOutput>}
-*/
-export default function init (module_or_path?: InitInput | Promise<InitInput>): Promise<InitOutput>;
+ * If `module_or_path` is {RequestInfo} or {URL}, makes a request and
+ * for everything else, calls `WebAssembly.instantiate` directly.
+ *
+ * @param {{ module_or_path: InitInput | Promise<InitInput> }} module_or_path - Passing `InitInput` directly is deprecated.
+ *
+ * @returns {Promise<InitOutput>}
+ */
+export default function __wbg_init (module_or_path?: { module_or_path: InitInput | Promise<InitInput> } | InitInput | Promise<InitInput>): Promise<InitOutput>;
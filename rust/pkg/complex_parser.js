@@ -1,134 +1,1129 @@
-let wasm;
+/* @ts-self-types="./complex_parser.d.ts" */
 
-let cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
+export class Evaluator {
+    static __wrap(ptr) {
+        const obj = Object.create(Evaluator.prototype);
+        obj.__wbg_ptr = ptr;
+        EvaluatorFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        EvaluatorFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_evaluator_free(ptr, 0);
+    }
+    cancel() {
+        wasm.evaluator_cancel(this.__wbg_ptr);
+    }
+    /**
+     * @returns {boolean}
+     */
+    get cancelled() {
+        const ret = wasm.evaluator_cancelled(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get done() {
+        const ret = wasm.evaluator_done(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {Plot}
+     */
+    finish() {
+        const ptr = this.__destroy_into_raw();
+        const ret = wasm.evaluator_finish(ptr);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return Plot.__wrap(ret[0]);
+    }
+    /**
+     * @param {string} input
+     * @param {number} n
+     * @param {boolean} coarse_to_fine
+     */
+    constructor(input, n, coarse_to_fine) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.evaluator_new(ptr0, len0, n, coarse_to_fine);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0];
+        EvaluatorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @param {string} input
+     * @param {number} n
+     * @param {string} domain
+     * @param {boolean} coarse_to_fine
+     * @returns {Evaluator}
+     */
+    static onDomain(input, n, domain, coarse_to_fine) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(domain, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.evaluator_onDomain(ptr0, len0, n, ptr1, len1, coarse_to_fine);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return Evaluator.__wrap(ret[0]);
+    }
+    /**
+     * @returns {Plot}
+     */
+    preview() {
+        const ret = wasm.evaluator_preview(this.__wbg_ptr);
+        return Plot.__wrap(ret);
+    }
+    /**
+     * @returns {number}
+     */
+    progress() {
+        const ret = wasm.evaluator_progress(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @param {number} max_points
+     * @returns {boolean}
+     */
+    step(max_points) {
+        const ret = wasm.evaluator_step(this.__wbg_ptr, max_points);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return ret[0] !== 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get stride() {
+        const ret = wasm.evaluator_stride(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+}
+if (Symbol.dispose) Evaluator.prototype[Symbol.dispose] = Evaluator.prototype.free;
 
-cachedTextDecoder.decode();
+export class Plot {
+    static __wrap(ptr) {
+        const obj = Object.create(Plot.prototype);
+        obj.__wbg_ptr = ptr;
+        PlotFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PlotFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_plot_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get cols() {
+        const ret = wasm.__wbg_get_plot_cols(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get n() {
+        const ret = wasm.__wbg_get_plot_n(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get rows() {
+        const ret = wasm.__wbg_get_plot_rows(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @param {string} quantity
+     * @param {Float32Array} levels
+     * @param {number} graph_type
+     * @param {string} height_map
+     * @returns {Polylines}
+     */
+    contoursOnSurface(quantity, levels, graph_type, height_map) {
+        const ptr0 = passStringToWasm0(quantity, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passArrayF32ToWasm0(levels, wasm.__wbindgen_malloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ptr2 = passStringToWasm0(height_map, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len2 = WASM_VECTOR_LEN;
+        const ret = wasm.plot_contoursOnSurface(this.__wbg_ptr, ptr0, len0, ptr1, len1, graph_type, ptr2, len2);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return Polylines.__wrap(ret[0]);
+    }
+    /**
+     * @param {string} quantity
+     * @param {Float32Array} levels
+     * @returns {Polylines}
+     */
+    contours(quantity, levels) {
+        const ptr0 = passStringToWasm0(quantity, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passArrayF32ToWasm0(levels, wasm.__wbindgen_malloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.plot_contours(this.__wbg_ptr, ptr0, len0, ptr1, len1);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return Polylines.__wrap(ret[0]);
+    }
+    /**
+     * @param {string} input
+     * @param {number} n
+     */
+    constructor(input, n) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.plot_new(ptr0, len0, n);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0];
+        PlotFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @param {string} input
+     * @param {number} n
+     * @param {string} domain
+     * @returns {Plot}
+     */
+    static onDomain(input, n, domain) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(domain, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.plot_onDomain(ptr0, len0, n, ptr1, len1);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return Plot.__wrap(ret[0]);
+    }
+    /**
+     * @param {number} graph_type
+     * @param {string} height_map
+     * @param {string} colormap
+     * @param {number} saturation
+     * @param {number} value
+     * @param {number} opacity
+     * @param {string} alpha
+     * @returns {PlotResult}
+     */
+    render(graph_type, height_map, colormap, saturation, value, opacity, alpha) {
+        const ptr0 = passStringToWasm0(height_map, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(colormap, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ptr2 = passStringToWasm0(alpha, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len2 = WASM_VECTOR_LEN;
+        const ret = wasm.plot_render(this.__wbg_ptr, graph_type, ptr0, len0, ptr1, len1, saturation, value, opacity, ptr2, len2);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return PlotResult.__wrap(ret[0]);
+    }
+    /**
+     * @returns {PlotStats}
+     */
+    stats() {
+        const ret = wasm.plot_stats(this.__wbg_ptr);
+        return PlotStats.__wrap(ret);
+    }
+}
+if (Symbol.dispose) Plot.prototype[Symbol.dispose] = Plot.prototype.free;
+
+export class PlotResult {
+    static __wrap(ptr) {
+        const obj = Object.create(PlotResult.prototype);
+        obj.__wbg_ptr = ptr;
+        PlotResultFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PlotResultFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_plotresult_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get colorChannels() {
+        const ret = wasm.__wbg_get_plotresult_colorChannels(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get cols() {
+        const ret = wasm.__wbg_get_plotresult_cols(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get heightMax() {
+        const ret = wasm.__wbg_get_plotresult_heightMax(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get heightMin() {
+        const ret = wasm.__wbg_get_plotresult_heightMin(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get imMax() {
+        const ret = wasm.__wbg_get_plotresult_imMax(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get imMin() {
+        const ret = wasm.__wbg_get_plotresult_imMin(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get reMax() {
+        const ret = wasm.__wbg_get_plotresult_reMax(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get reMin() {
+        const ret = wasm.__wbg_get_plotresult_reMin(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get rows() {
+        const ret = wasm.__wbg_get_plotresult_rows(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    get colors() {
+        const ret = wasm.plotresult_colors(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    get indices() {
+        const ret = wasm.plotresult_indices(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {Uint8Array}
+     */
+    get mask() {
+        const ret = wasm.plotresult_mask(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    get normals() {
+        const ret = wasm.plotresult_normals(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    get positions() {
+        const ret = wasm.plotresult_positions(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    get values() {
+        const ret = wasm.plotresult_values(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get vertexCount() {
+        const ret = wasm.plotresult_vertexCount(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+}
+if (Symbol.dispose) PlotResult.prototype[Symbol.dispose] = PlotResult.prototype.free;
+
+export class PlotStats {
+    static __wrap(ptr) {
+        const obj = Object.create(PlotStats.prototype);
+        obj.__wbg_ptr = ptr;
+        PlotStatsFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PlotStatsFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_plotstats_free(ptr, 0);
+    }
+    /**
+     * @returns {Summary}
+     */
+    get im() {
+        const ret = wasm.__wbg_get_plotstats_im(this.__wbg_ptr);
+        return Summary.__wrap(ret);
+    }
+    /**
+     * @returns {number}
+     */
+    get infinite() {
+        const ret = wasm.__wbg_get_plotstats_infinite(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get nan() {
+        const ret = wasm.__wbg_get_plotstats_nan(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Summary}
+     */
+    get norm() {
+        const ret = wasm.__wbg_get_plotstats_norm(this.__wbg_ptr);
+        return Summary.__wrap(ret);
+    }
+    /**
+     * @returns {Summary}
+     */
+    get re() {
+        const ret = wasm.__wbg_get_plotstats_re(this.__wbg_ptr);
+        return Summary.__wrap(ret);
+    }
+    /**
+     * @returns {number}
+     */
+    get total() {
+        const ret = wasm.__wbg_get_plotstats_total(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get nonFinite() {
+        const ret = wasm.plotstats_nonFinite(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+}
+if (Symbol.dispose) PlotStats.prototype[Symbol.dispose] = PlotStats.prototype.free;
 
-let cachegetUint8Memory0 = null;
-function getUint8Memory0() {
-    if (cachegetUint8Memory0 === null || cachegetUint8Memory0.buffer !== wasm.memory.buffer) {
-        cachegetUint8Memory0 = new Uint8Array(wasm.memory.buffer);
+export class Polylines {
+    static __wrap(ptr) {
+        const obj = Object.create(Polylines.prototype);
+        obj.__wbg_ptr = ptr;
+        PolylinesFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PolylinesFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_polylines_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get lineCount() {
+        const ret = wasm.polylines_lineCount(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    get offsets() {
+        const ret = wasm.polylines_offsets(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    get points() {
+        const ret = wasm.polylines_points(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @param {string} stroke
+     * @param {number} stroke_width
+     * @returns {string}
+     */
+    toSvg(stroke, stroke_width) {
+        let deferred2_0;
+        let deferred2_1;
+        try {
+            const ptr0 = passStringToWasm0(stroke, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len0 = WASM_VECTOR_LEN;
+            const ret = wasm.polylines_toSvg(this.__wbg_ptr, ptr0, len0, stroke_width);
+            deferred2_0 = ret[0];
+            deferred2_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+        }
     }
-    return cachegetUint8Memory0;
+}
+if (Symbol.dispose) Polylines.prototype[Symbol.dispose] = Polylines.prototype.free;
+
+export class Summary {
+    static __wrap(ptr) {
+        const obj = Object.create(Summary.prototype);
+        obj.__wbg_ptr = ptr;
+        SummaryFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SummaryFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_summary_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get count() {
+        const ret = wasm.__wbg_get_summary_count(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get max() {
+        const ret = wasm.__wbg_get_summary_max(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get mean() {
+        const ret = wasm.__wbg_get_summary_mean(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get median() {
+        const ret = wasm.__wbg_get_summary_median(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get min() {
+        const ret = wasm.__wbg_get_summary_min(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get p1() {
+        const ret = wasm.__wbg_get_summary_p1(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get p5() {
+        const ret = wasm.__wbg_get_summary_p5(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get p95() {
+        const ret = wasm.__wbg_get_summary_p95(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get p99() {
+        const ret = wasm.__wbg_get_summary_p99(this.__wbg_ptr);
+        return ret;
+    }
+}
+if (Symbol.dispose) Summary.prototype[Symbol.dispose] = Summary.prototype.free;
+
+export class SvgExport {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SvgExportFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_svgexport_free(ptr, 0);
+    }
+    /**
+     * @returns {boolean}
+     */
+    get axes() {
+        const ret = wasm.__wbg_get_svgexport_axes(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get colorbar() {
+        const ret = wasm.__wbg_get_svgexport_colorbar(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get markers() {
+        const ret = wasm.__wbg_get_svgexport_markers(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @param {boolean} arg0
+     */
+    set axes(arg0) {
+        wasm.__wbg_set_svgexport_axes(this.__wbg_ptr, arg0);
+    }
+    /**
+     * @param {boolean} arg0
+     */
+    set colorbar(arg0) {
+        wasm.__wbg_set_svgexport_colorbar(this.__wbg_ptr, arg0);
+    }
+    /**
+     * @param {boolean} arg0
+     */
+    set markers(arg0) {
+        wasm.__wbg_set_svgexport_markers(this.__wbg_ptr, arg0);
+    }
+    /**
+     * @param {Polylines} lines
+     * @param {string} stroke
+     * @param {number} stroke_width
+     */
+    addLines(lines, stroke, stroke_width) {
+        _assertClass(lines, Polylines);
+        const ptr0 = passStringToWasm0(stroke, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        wasm.svgexport_addLines(this.__wbg_ptr, lines.__wbg_ptr, ptr0, len0, stroke_width);
+    }
+    /**
+     * @param {Plot} plot
+     * @param {number} graph_type
+     * @param {string} colormap
+     * @param {number} saturation
+     * @param {number} value
+     */
+    constructor(plot, graph_type, colormap, saturation, value) {
+        _assertClass(plot, Plot);
+        const ptr0 = passStringToWasm0(colormap, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.svgexport_new(plot.__wbg_ptr, graph_type, ptr0, len0, saturation, value);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0];
+        SvgExportFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @param {number} size
+     * @returns {string}
+     */
+    toSvg(size) {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.svgexport_toSvg(this.__wbg_ptr, size);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+}
+if (Symbol.dispose) SvgExport.prototype[Symbol.dispose] = SvgExport.prototype.free;
+
+export class VectorField {
+    static __wrap(ptr) {
+        const obj = Object.create(VectorField.prototype);
+        obj.__wbg_ptr = ptr;
+        VectorFieldFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        VectorFieldFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_vectorfield_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get glyphCount() {
+        const ret = wasm.vectorfield_glyphCount(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    get glyphs() {
+        const ret = wasm.vectorfield_glyphs(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @param {string} input
+     * @param {number} spacing
+     * @param {number} seeds
+     * @param {number} step
+     * @param {number} max_steps
+     */
+    constructor(input, spacing, seeds, step, max_steps) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.vectorfield_new(ptr0, len0, spacing, seeds, step, max_steps);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0];
+        VectorFieldFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @param {Plot} plot
+     * @param {number} graph_type
+     * @param {string} height_map
+     * @returns {VectorField}
+     */
+    onSurface(plot, graph_type, height_map) {
+        _assertClass(plot, Plot);
+        const ptr0 = passStringToWasm0(height_map, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.vectorfield_onSurface(this.__wbg_ptr, plot.__wbg_ptr, graph_type, ptr0, len0);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return VectorField.__wrap(ret[0]);
+    }
+    /**
+     * @returns {Polylines}
+     */
+    get streamlines() {
+        const ret = wasm.vectorfield_streamlines(this.__wbg_ptr);
+        return Polylines.__wrap(ret);
+    }
+}
+if (Symbol.dispose) VectorField.prototype[Symbol.dispose] = VectorField.prototype.free;
+
+/**
+ * @enum {0 | 1 | 2}
+ */
+export const VertexKind = Object.freeze({
+    Undefined: 0, "0": "Undefined",
+    Finite: 1, "1": "Finite",
+    Pole: 2, "2": "Pole",
+});
+
+/**
+ * @returns {any[]}
+ */
+export function colormap_names() {
+    const ret = wasm.colormap_names();
+    var v1 = getArrayJsValueFromWasm0(ret[0], ret[1]);
+    wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+    return v1;
+}
+
+/**
+ * @param {string} input
+ * @param {string} curves
+ * @param {number} tolerance
+ * @param {boolean} split
+ * @returns {Polylines}
+ */
+export function conformal_grid(input, curves, tolerance, split) {
+    const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ptr1 = passStringToWasm0(curves, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len1 = WASM_VECTOR_LEN;
+    const ret = wasm.conformal_grid(ptr0, len0, ptr1, len1, tolerance, split);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return Polylines.__wrap(ret[0]);
+}
+
+/**
+ * @param {number} graph_type
+ * @returns {string}
+ */
+export function default_colormap(graph_type) {
+    let deferred1_0;
+    let deferred1_1;
+    try {
+        const ret = wasm.default_colormap(graph_type);
+        deferred1_0 = ret[0];
+        deferred1_1 = ret[1];
+        return getStringFromWasm0(ret[0], ret[1]);
+    } finally {
+        wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+    }
+}
+
+/**
+ * @param {number} graph_type
+ * @returns {number}
+ */
+export function default_saturation(graph_type) {
+    const ret = wasm.default_saturation(graph_type);
+    return ret;
+}
+
+/**
+ * @param {string} input
+ * @param {number} n
+ * @param {number} graph_type
+ * @param {boolean} log_height
+ * @returns {Float32Array}
+ */
+export function evaluate(input, n, graph_type, log_height) {
+    const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.evaluate(ptr0, len0, n, graph_type, log_height);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    var v2 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+    return v2;
+}
+
+/**
+ * @param {string} input
+ * @param {number} n
+ * @param {number} graph_type
+ * @param {string} height_map
+ * @param {string} colormap
+ * @param {number} saturation
+ * @param {number} value
+ * @param {number} opacity
+ * @param {string} alpha
+ * @returns {PlotResult}
+ */
+export function evaluate_styled(input, n, graph_type, height_map, colormap, saturation, value, opacity, alpha) {
+    const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ptr1 = passStringToWasm0(height_map, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len1 = WASM_VECTOR_LEN;
+    const ptr2 = passStringToWasm0(colormap, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len2 = WASM_VECTOR_LEN;
+    const ptr3 = passStringToWasm0(alpha, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len3 = WASM_VECTOR_LEN;
+    const ret = wasm.evaluate_styled(ptr0, len0, n, graph_type, ptr1, len1, ptr2, len2, saturation, value, opacity, ptr3, len3);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return PlotResult.__wrap(ret[0]);
+}
+function __wbg_get_imports() {
+    const import0 = {
+        __proto__: null,
+        __wbg___wbindgen_throw_41e9ee4f547fc59a: function(arg0, arg1) {
+            throw new Error(getStringFromWasm0(arg0, arg1));
+        },
+        __wbindgen_generic_0000000000000001: function(arg0, arg1) {
+            // Cast intrinsic for `Ref(Slice(F32)) -> NamedExternref("Float32Array")`.
+            const ret = getArrayF32FromWasm0(arg0, arg1);
+            return ret;
+        },
+        __wbindgen_generic_0000000000000002: function(arg0, arg1) {
+            // Cast intrinsic for `Ref(Slice(U32)) -> NamedExternref("Uint32Array")`.
+            const ret = getArrayU32FromWasm0(arg0, arg1);
+            return ret;
+        },
+        __wbindgen_generic_0000000000000003: function(arg0, arg1) {
+            // Cast intrinsic for `Ref(Slice(U8)) -> NamedExternref("Uint8Array")`.
+            const ret = getArrayU8FromWasm0(arg0, arg1);
+            return ret;
+        },
+        __wbindgen_generic_0000000000000004: function(arg0, arg1) {
+            // Cast intrinsic for `Ref(String) -> Externref`.
+            const ret = getStringFromWasm0(arg0, arg1);
+            return ret;
+        },
+        __wbindgen_init_externref_table: function() {
+            const table = wasm.__wbindgen_externrefs;
+            const offset = table.grow(4);
+            table.set(0, undefined);
+            table.set(offset + 0, undefined);
+            table.set(offset + 1, null);
+            table.set(offset + 2, true);
+            table.set(offset + 3, false);
+        },
+    };
+    return {
+        __proto__: null,
+        "./complex_parser_bg.js": import0,
+    };
+}
+
+const EvaluatorFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_evaluator_free(ptr, 1));
+const PlotFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_plot_free(ptr, 1));
+const PlotResultFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_plotresult_free(ptr, 1));
+const PlotStatsFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_plotstats_free(ptr, 1));
+const PolylinesFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_polylines_free(ptr, 1));
+const SummaryFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_summary_free(ptr, 1));
+const SvgExportFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_svgexport_free(ptr, 1));
+const VectorFieldFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_vectorfield_free(ptr, 1));
+
+function _assertClass(instance, klass) {
+    if (!(instance instanceof klass)) {
+        throw new Error(`expected instance of ${klass.name}`);
+    }
+}
+
+function getArrayF32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getFloat32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
+function getArrayJsValueFromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    const mem = getDataViewMemory0();
+    const result = [];
+    for (let i = ptr; i < ptr + 4 * len; i += 4) {
+        result.push(wasm.__wbindgen_externrefs.get(mem.getUint32(i, true)));
+    }
+    wasm.__externref_drop_slice(ptr, len);
+    return result;
+}
+
+function getArrayU32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getUint32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
+function getArrayU8FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
+}
+
+let cachedDataViewMemory0 = null;
+function getDataViewMemory0() {
+    if (cachedDataViewMemory0 === null || cachedDataViewMemory0.buffer.detached === true || (cachedDataViewMemory0.buffer.detached === undefined && cachedDataViewMemory0.buffer !== wasm.memory.buffer)) {
+        cachedDataViewMemory0 = new DataView(wasm.memory.buffer);
+    }
+    return cachedDataViewMemory0;
+}
+
+let cachedFloat32ArrayMemory0 = null;
+function getFloat32ArrayMemory0() {
+    if (cachedFloat32ArrayMemory0 === null || cachedFloat32ArrayMemory0.byteLength === 0) {
+        cachedFloat32ArrayMemory0 = new Float32Array(wasm.memory.buffer);
+    }
+    return cachedFloat32ArrayMemory0;
 }
 
 function getStringFromWasm0(ptr, len) {
-    return cachedTextDecoder.decode(getUint8Memory0().subarray(ptr, ptr + len));
+    return decodeText(ptr >>> 0, len);
 }
 
-let WASM_VECTOR_LEN = 0;
+let cachedUint32ArrayMemory0 = null;
+function getUint32ArrayMemory0() {
+    if (cachedUint32ArrayMemory0 === null || cachedUint32ArrayMemory0.byteLength === 0) {
+        cachedUint32ArrayMemory0 = new Uint32Array(wasm.memory.buffer);
+    }
+    return cachedUint32ArrayMemory0;
+}
+
+let cachedUint8ArrayMemory0 = null;
+function getUint8ArrayMemory0() {
+    if (cachedUint8ArrayMemory0 === null || cachedUint8ArrayMemory0.byteLength === 0) {
+        cachedUint8ArrayMemory0 = new Uint8Array(wasm.memory.buffer);
+    }
+    return cachedUint8ArrayMemory0;
+}
 
-let cachedTextEncoder = new TextEncoder('utf-8');
-
-const encodeString =
-    typeof cachedTextEncoder.encodeInto === 'function'
-        ? function (arg, view) {
-              return cachedTextEncoder.encodeInto(arg, view);
-          }
-        : function (arg, view) {
-              const buf = cachedTextEncoder.encode(arg);
-              view.set(buf);
-              return {
-                  read: arg.length,
-                  written: buf.length
-              };
-          };
+function passArrayF32ToWasm0(arg, malloc) {
+    const ptr = malloc(arg.length * 4, 4) >>> 0;
+    getFloat32ArrayMemory0().set(arg, ptr / 4);
+    WASM_VECTOR_LEN = arg.length;
+    return ptr;
+}
 
 function passStringToWasm0(arg, malloc, realloc) {
     if (realloc === undefined) {
         const buf = cachedTextEncoder.encode(arg);
-        const ptr = malloc(buf.length);
-        getUint8Memory0()
-            .subarray(ptr, ptr + buf.length)
-            .set(buf);
+        const ptr = malloc(buf.length, 1) >>> 0;
+        getUint8ArrayMemory0().subarray(ptr, ptr + buf.length).set(buf);
         WASM_VECTOR_LEN = buf.length;
         return ptr;
     }
 
     let len = arg.length;
-    let ptr = malloc(len);
+    let ptr = malloc(len, 1) >>> 0;
 
-    const mem = getUint8Memory0();
+    const mem = getUint8ArrayMemory0();
 
     let offset = 0;
 
     for (; offset < len; offset++) {
         const code = arg.charCodeAt(offset);
-        if (code > 0x7f) break;
+        if (code > 0x7F) break;
         mem[ptr + offset] = code;
     }
-
     if (offset !== len) {
         if (offset !== 0) {
             arg = arg.slice(offset);
         }
-        ptr = realloc(ptr, len, (len = offset + arg.length * 3));
-        const view = getUint8Memory0().subarray(ptr + offset, ptr + len);
-        const ret = encodeString(arg, view);
+        ptr = realloc(ptr, len, len = offset + arg.length * 3, 1) >>> 0;
+        const view = getUint8ArrayMemory0().subarray(ptr + offset, ptr + len);
+        const ret = cachedTextEncoder.encodeInto(arg, view);
 
         offset += ret.written;
+        ptr = realloc(ptr, len, offset, 1) >>> 0;
     }
 
     WASM_VECTOR_LEN = offset;
     return ptr;
 }
 
-let cachegetInt32Memory0 = null;
-function getInt32Memory0() {
-    if (cachegetInt32Memory0 === null || cachegetInt32Memory0.buffer !== wasm.memory.buffer) {
-        cachegetInt32Memory0 = new Int32Array(wasm.memory.buffer);
-    }
-    return cachegetInt32Memory0;
+function takeFromExternrefTable0(idx) {
+    const value = wasm.__wbindgen_externrefs.get(idx);
+    wasm.__externref_table_dealloc(idx);
+    return value;
 }
 
-let cachegetFloat32Memory0 = null;
-function getFloat32Memory0() {
-    if (cachegetFloat32Memory0 === null || cachegetFloat32Memory0.buffer !== wasm.memory.buffer) {
-        cachegetFloat32Memory0 = new Float32Array(wasm.memory.buffer);
+let cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
+cachedTextDecoder.decode();
+const MAX_SAFARI_DECODE_BYTES = 2146435072;
+let numBytesDecoded = 0;
+function decodeText(ptr, len) {
+    numBytesDecoded += len;
+    if (numBytesDecoded >= MAX_SAFARI_DECODE_BYTES) {
+        cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
+        cachedTextDecoder.decode();
+        numBytesDecoded = len;
     }
-    return cachegetFloat32Memory0;
+    return cachedTextDecoder.decode(getUint8ArrayMemory0().subarray(ptr, ptr + len));
 }
 
-function getArrayF32FromWasm0(ptr, len) {
-    return getFloat32Memory0().subarray(ptr / 4, ptr / 4 + len);
+const cachedTextEncoder = new TextEncoder();
+
+if (!('encodeInto' in cachedTextEncoder)) {
+    cachedTextEncoder.encodeInto = function (arg, view) {
+        const buf = cachedTextEncoder.encode(arg);
+        view.set(buf);
+        return {
+            read: arg.length,
+            written: buf.length
+        };
+    };
 }
-/**
- * @param {string} input
- * @param {number} n
- * @param {number} graph_type
- * @param {boolean} log_height
- * @returns {Float32Array}
- */
-export function evaluate(input, n, graph_type, log_height) {
-    try {
-        const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
-        var ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-        var len0 = WASM_VECTOR_LEN;
-        wasm.evaluate(retptr, ptr0, len0, n, graph_type, log_height);
-        var r0 = getInt32Memory0()[retptr / 4 + 0];
-        var r1 = getInt32Memory0()[retptr / 4 + 1];
-        var v1 = getArrayF32FromWasm0(r0, r1).slice();
-        wasm.__wbindgen_free(r0, r1 * 4);
-        return v1;
-    } finally {
-        wasm.__wbindgen_add_to_stack_pointer(16);
-    }
+
+let WASM_VECTOR_LEN = 0;
+
+let wasmModule, wasmInstance, wasm;
+function __wbg_finalize_init(instance, module) {
+    wasmInstance = instance;
+    wasm = instance.exports;
+    wasmModule = module;
+    cachedDataViewMemory0 = null;
+    cachedFloat32ArrayMemory0 = null;
+    cachedUint32ArrayMemory0 = null;
+    cachedUint8ArrayMemory0 = null;
+    wasm.__wbindgen_start();
+    return wasm;
 }
 
-async function load(module, imports) {
+async function __wbg_load(module, imports) {
     if (typeof Response === 'function' && module instanceof Response) {
+        if (!module.ok) {
+            throw new Error(`failed to fetch Wasm: ${module.status} ${module.statusText} fetching '${module.url}'`);
+        }
+
         if (typeof WebAssembly.instantiateStreaming === 'function') {
             try {
                 return await WebAssembly.instantiateStreaming(module, imports);
             } catch (e) {
-                if (module.headers.get('Content-Type') != 'application/wasm') {
-                    console.warn(
-                        '`WebAssembly.instantiateStreaming` failed because your server does not serve wasm with `application/wasm` MIME type. Falling back to `WebAssembly.instantiate` which is slower. Original error:\n',
-                        e
-                    );
-                } else {
-                    throw e;
-                }
+                const validResponse = expectedResponseType(module.type);
+
+                if (validResponse && module.headers.get('Content-Type') !== 'application/wasm') {
+                    console.warn("`WebAssembly.instantiateStreaming` failed because your server does not serve Wasm with `application/wasm` MIME type. Falling back to `WebAssembly.instantiate` which is slower. Original error:\n", e);
+
+                } else { throw e; }
             }
         }
 
@@ -143,35 +1138,66 @@ async function load(module, imports) {
             return instance;
         }
     }
+
+    function expectedResponseType(type) {
+        switch (type) {
+            case 'basic': case 'cors': case 'default': return true;
+        }
+        return false;
+    }
 }
 
-async function init(input) {
-    if (typeof input === 'undefined') {
-        input = new URL('complex_parser_bg.wasm', import.meta.url);
+function initSync(module) {
+    if (wasm !== undefined) return wasm;
+
+
+    if (module !== undefined) {
+        if (Object.getPrototypeOf(module) === Object.prototype) {
+            ({module} = module)
+        } else {
+            console.warn('using deprecated parameters for `initSync()`; pass a single object instead')
+        }
     }
-    const imports = {};
-    imports.wbg = {};
-    imports.wbg.__wbindgen_throw = function (arg0, arg1) {
-        throw new Error(getStringFromWasm0(arg0, arg1));
-    };
 
-    if (
-        typeof input === 'string' ||
-        (typeof Request === 'function' && input instanceof Request) ||
-        (typeof URL === 'function' && input instanceof URL)
-    ) {
-        input = fetch(input);
+    const imports = __wbg_get_imports();
+    if (!(module instanceof WebAssembly.Module)) {
+        module = new WebAssembly.Module(module);
     }
+    const instance = new WebAssembly.Instance(module, imports);
+    return __wbg_finalize_init(instance, module);
+}
 
-    const { instance, module } = await load(await input, imports);
+async function __wbg_init(module_or_path) {
+    if (wasm !== undefined) return wasm;
 
-    wasm = instance.exports;
-    init.__wbindgen_wasm_module = module;
 
-    return wasm;
+    if (module_or_path !== undefined) {
+        if (Object.getPrototypeOf(module_or_path) === Object.prototype) {
+            ({module_or_path} = module_or_path)
+        } else {
+            console.warn('using deprecated parameters for the initialization function; pass a single object instead')
+        }
+    }
+
+    if (module_or_path === undefined) {
+        module_or_path = new URL('complex_parser_bg.wasm', import.meta.url);
+    }
+    const imports = __wbg_get_imports();
+
+    if (typeof module_or_path === 'string' || (typeof Request === 'function' && module_or_path instanceof Request) || (typeof URL === 'function' && module_or_path instanceof URL)) {
+        module_or_path = fetch(module_or_path);
+    }
+
+    const { instance, module } = await __wbg_load(await module_or_path, imports);
+
+    return __wbg_finalize_init(instance, module);
 }
 
-export default init;
+export { initSync, __wbg_init as default };
 
-window.init = init;
+window.init = __wbg_init;
 window.evaluate = evaluate;
+window.evaluate_styled = evaluate_styled;
+window.Plot = Plot;
+window.default_colormap = default_colormap;
+window.default_saturation = default_saturation;
@@ -1,8 +1,93 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
-export function evaluate(a: number, b: number, c: number, d: number, e: number, f: number): void;
-export function __wbindgen_add_to_stack_pointer(a: number): number;
-export function __wbindgen_malloc(a: number): number;
-export function __wbindgen_realloc(a: number, b: number, c: number): number;
-export function __wbindgen_free(a: number, b: number): void;
+export const __wbg_evaluator_free: (a: number, b: number) => void;
+export const __wbg_get_plot_cols: (a: number) => number;
+export const __wbg_get_plot_n: (a: number) => number;
+export const __wbg_get_plot_rows: (a: number) => number;
+export const __wbg_get_plotresult_colorChannels: (a: number) => number;
+export const __wbg_get_plotresult_cols: (a: number) => number;
+export const __wbg_get_plotresult_heightMax: (a: number) => number;
+export const __wbg_get_plotresult_heightMin: (a: number) => number;
+export const __wbg_get_plotresult_imMax: (a: number) => number;
+export const __wbg_get_plotresult_imMin: (a: number) => number;
+export const __wbg_get_plotresult_reMax: (a: number) => number;
+export const __wbg_get_plotresult_reMin: (a: number) => number;
+export const __wbg_get_plotresult_rows: (a: number) => number;
+export const __wbg_get_plotstats_im: (a: number) => number;
+export const __wbg_get_plotstats_infinite: (a: number) => number;
+export const __wbg_get_plotstats_nan: (a: number) => number;
+export const __wbg_get_plotstats_norm: (a: number) => number;
+export const __wbg_get_plotstats_re: (a: number) => number;
+export const __wbg_get_plotstats_total: (a: number) => number;
+export const __wbg_get_summary_count: (a: number) => number;
+export const __wbg_get_summary_max: (a: number) => number;
+export const __wbg_get_summary_mean: (a: number) => number;
+export const __wbg_get_summary_median: (a: number) => number;
+export const __wbg_get_summary_min: (a: number) => number;
+export const __wbg_get_summary_p1: (a: number) => number;
+export const __wbg_get_summary_p5: (a: number) => number;
+export const __wbg_get_summary_p95: (a: number) => number;
+export const __wbg_get_summary_p99: (a: number) => number;
+export const __wbg_get_svgexport_axes: (a: number) => number;
+export const __wbg_get_svgexport_colorbar: (a: number) => number;
+export const __wbg_get_svgexport_markers: (a: number) => number;
+export const __wbg_plot_free: (a: number, b: number) => void;
+export const __wbg_plotresult_free: (a: number, b: number) => void;
+export const __wbg_plotstats_free: (a: number, b: number) => void;
+export const __wbg_polylines_free: (a: number, b: number) => void;
+export const __wbg_set_svgexport_axes: (a: number, b: number) => void;
+export const __wbg_set_svgexport_colorbar: (a: number, b: number) => void;
+export const __wbg_set_svgexport_markers: (a: number, b: number) => void;
+export const __wbg_summary_free: (a: number, b: number) => void;
+export const __wbg_svgexport_free: (a: number, b: number) => void;
+export const __wbg_vectorfield_free: (a: number, b: number) => void;
+export const colormap_names: () => [number, number];
+export const conformal_grid: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+export const default_colormap: (a: number) => [number, number];
+export const default_saturation: (a: number) => number;
+export const evaluate: (a: number, b: number, c: number, d: number, e: number) => [number, number, number, number];
+export const evaluate_styled: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number) => [number, number, number];
+export const evaluator_cancel: (a: number) => void;
+export const evaluator_cancelled: (a: number) => number;
+export const evaluator_done: (a: number) => number;
+export const evaluator_finish: (a: number) => [number, number, number];
+export const evaluator_new: (a: number, b: number, c: number, d: number) => [number, number, number];
+export const evaluator_onDomain: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+export const evaluator_preview: (a: number) => number;
+export const evaluator_progress: (a: number) => number;
+export const evaluator_step: (a: number, b: number) => [number, number, number];
+export const evaluator_stride: (a: number) => number;
+export const plot_contours: (a: number, b: number, c: number, d: number, e: number) => [number, number, number];
+export const plot_contoursOnSurface: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => [number, number, number];
+export const plot_new: (a: number, b: number, c: number) => [number, number, number];
+export const plot_onDomain: (a: number, b: number, c: number, d: number, e: number) => [number, number, number];
+export const plot_render: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number) => [number, number, number];
+export const plot_stats: (a: number) => number;
+export const plotresult_colors: (a: number) => any;
+export const plotresult_indices: (a: number) => any;
+export const plotresult_mask: (a: number) => any;
+export const plotresult_normals: (a: number) => any;
+export const plotresult_positions: (a: number) => any;
+export const plotresult_values: (a: number) => any;
+export const plotresult_vertexCount: (a: number) => number;
+export const plotstats_nonFinite: (a: number) => number;
+export const polylines_lineCount: (a: number) => number;
+export const polylines_offsets: (a: number) => any;
+export const polylines_points: (a: number) => any;
+export const polylines_toSvg: (a: number, b: number, c: number, d: number) => [number, number];
+export const svgexport_addLines: (a: number, b: number, c: number, d: number, e: number) => void;
+export const svgexport_new: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+export const svgexport_toSvg: (a: number, b: number) => [number, number];
+export const vectorfield_glyphCount: (a: number) => number;
+export const vectorfield_glyphs: (a: number) => any;
+export const vectorfield_new: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
+export const vectorfield_onSurface: (a: number, b: number, c: number, d: number, e: number) => [number, number, number];
+export const vectorfield_streamlines: (a: number) => number;
+export const __wbindgen_externrefs: WebAssembly.Table;
+export const __externref_drop_slice: (a: number, b: number) => void;
+export const __wbindgen_free: (a: number, b: number, c: number) => void;
+export const __wbindgen_malloc: (a: number, b: number) => number;
+export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
+export const __externref_table_dealloc: (a: number) => void;
+export const __wbindgen_start: () => void;
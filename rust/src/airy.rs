@@ -0,0 +1,677 @@
+use crate::bessel::ik;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+// the Maclaurin series are used inside this radius, the Bessel functions of
+// order 1/3 and 2/3 (with their Hankel expansions far out) outside
+const SERIES_RADIUS: f64 = 1.;
+// Hi comes from its Maclaurin series inside the first radius, from its
+// integral up to the second and from its asymptotic expansion beyond, whose
+// smallest term e^(-2|z|^(3/2) / 3) is below f64 precision there
+const HI_SERIES_RADIUS: f64 = 3.;
+const HI_ASYMPTOTIC_RADIUS: f64 = 15.;
+// the length of the pieces of the integral
+const PANEL_WIDTH: f64 = 0.5;
+const EPS: f64 = 1e-17;
+// Ai(0) and -Ai'(0)
+const AI_ZERO: f64 = 0.3550280538878172;
+const AIP_ZERO: f64 = 0.2588194037928068;
+// Γ(1/3) and Γ(2/3)
+const GAMMA_THIRD: f64 = 2.6789385347077475;
+const GAMMA_TWO_THIRDS: f64 = 1.3541179394264005;
+const SQRT_3: f64 = 1.7320508075688772;
+// ω = e^(2πi/3)
+const OMEGA: Complex64 = Complex64::new(-0.5, 0.8660254037844386);
+// the nodes in (0, 1) and the weights of the 16 point Gauss-Legendre rule
+pub(crate) static GAUSS_LEGENDRE: [(f64, f64); 8] = [
+    (0.9894009349916499, 0.027152459411754096),
+    (0.9445750230732326, 0.062253523938647894),
+    (0.8656312023878318, 0.09515851168249279),
+    (0.755404408355003, 0.12462897125553388),
+    (0.6178762444026438, 0.14959598881657674),
+    (0.45801677765722737, 0.16915651939500254),
+    (0.2816035507792589, 0.18260341504492358),
+    (0.09501250983763744, 0.1894506104550685),
+];
+
+// e^(iπx)
+fn cis_pi(x: f64) -> Complex64 {
+    Complex64::from_polar(1., PI * x)
+}
+
+// all of them are entire and real on the real axis, so the only special
+// values are the infinite ones
+fn finish(z: Complex64, w: Complex64) -> Complex64 {
+    if z.is_nan() {
+        Complex64::new(f64::NAN, f64::NAN)
+    } else if !w.is_finite() {
+        Complex64::new(f64::INFINITY, 0.)
+    } else if z.im == 0. {
+        Complex64::new(w.re, 0.)
+    } else {
+        w
+    }
+}
+
+// --------------------------------------------------------------
+// airy functions
+// --------------------------------------------------------------
+// [Ai, Ai', Bi, Bi'] at z. for Re z >= 0 with ζ = 2/3 z^(3/2) (DLMF 9.6)
+//   Ai(z) = 1/π √(z/3) K_1/3(ζ),   Ai'(z) = -z/(π√3) K_2/3(ζ),
+//   Bi(z) = √(z/3) (I_-1/3(ζ) + I_1/3(ζ)), Bi'(z) = z/√3 (I_-2/3(ζ) + I_2/3(ζ)),
+// where |ph ζ| <= 3π/4 keeps clear of the cut of the Bessel functions, and
+// I_-ν = I_ν + 2/π sin(νπ) K_ν. the upper left quadrant comes from
+// Ai(u e^(2πi/3)) = 1/2 e^(πi/3) (Ai(u) - i Bi(u)) and
+// Bi(u e^(2πi/3)) = 1/2 e^(-πi/6) (3 Ai(u) + i Bi(u)) with Re u > 0, the
+// lower half-plane is the mirror image
+fn airy(z: Complex64) -> [Complex64; 4] {
+    if z.norm() <= SERIES_RADIUS {
+        return airy_series(z);
+    }
+    if z.re >= 0. {
+        let zeta = 2. / 3. * z * z.sqrt();
+        let (i1, k1) = ik(1. / 3., zeta);
+        let (i2, k2) = ik(2. / 3., zeta);
+        let r = (z / 3.).sqrt();
+        // 2/π sin(π/3)
+        let s = SQRT_3 / PI;
+        return [
+            r * k1 / PI,
+            -z / (PI * SQRT_3) * k2,
+            r * (2. * i1 + s * k1),
+            z / SQRT_3 * (2. * i2 + s * k2),
+        ];
+    }
+    if z.im < 0. {
+        return airy(z.conj()).map(|w| w.conj());
+    }
+    let i = Complex64::i();
+    let [ai, aip, bi, bip] = airy(OMEGA.conj() * z);
+    [
+        0.5 * cis_pi(1. / 3.) * (ai - i * bi),
+        0.5 * cis_pi(-1. / 3.) * (aip - i * bip),
+        0.5 * cis_pi(-1. / 6.) * (3. * ai + i * bi),
+        0.5 * cis_pi(-5. / 6.) * (3. * aip + i * bip),
+    ]
+}
+
+// Ai = Ai(0) f - Ai'(0) g and Bi = √3 (Ai(0) f + Ai'(0) g) (and the same for
+// the derivatives) with the solutions f = Σ 3^k (1/3)_k z^3k / (3k)! and
+// g = Σ 3^k (2/3)_k z^(3k+1) / (3k + 1)! of w'' = zw
+fn airy_series(z: Complex64) -> [Complex64; 4] {
+    let z3 = z * z * z;
+    let (mut f, mut g, mut fp, mut gp) = (
+        Complex64::new(1., 0.),
+        z,
+        z * z / 2.,
+        Complex64::new(1., 0.),
+    );
+    let (mut sf, mut sg, mut sfp, mut sgp) = (f, g, fp, gp);
+    for j in 0..40 {
+        let j = j as f64;
+        f *= z3 / ((3. * j + 2.) * (3. * j + 3.));
+        g *= z3 / ((3. * j + 3.) * (3. * j + 4.));
+        fp *= z3 / ((3. * j + 3.) * (3. * j + 5.));
+        gp *= z3 / ((3. * j + 1.) * (3. * j + 3.));
+        sf += f;
+        sg += g;
+        sfp += fp;
+        sgp += gp;
+        if f.norm() + g.norm() + fp.norm() + gp.norm() < EPS {
+            break;
+        }
+    }
+    [
+        AI_ZERO * sf - AIP_ZERO * sg,
+        AI_ZERO * sfp - AIP_ZERO * sgp,
+        SQRT_3 * (AI_ZERO * sf + AIP_ZERO * sg),
+        SQRT_3 * (AI_ZERO * sfp + AIP_ZERO * sgp),
+    ]
+}
+
+pub fn airy_ai(z: Complex64) -> Complex64 {
+    finish(z, airy(z)[0])
+}
+
+pub fn airy_ai_prime(z: Complex64) -> Complex64 {
+    finish(z, airy(z)[1])
+}
+
+pub fn airy_bi(z: Complex64) -> Complex64 {
+    finish(z, airy(z)[2])
+}
+
+pub fn airy_bi_prime(z: Complex64) -> Complex64 {
+    finish(z, airy(z)[3])
+}
+
+// --------------------------------------------------------------
+// scorer functions
+// --------------------------------------------------------------
+// Hi(z) = 1/π ∫_0^∞ exp(-t³/3 + zt) dt, in the upper half-plane (it's real
+// on the real axis). it only grows for |ph z| < π/3, elsewhere it goes like
+// -1/(πz), which its asymptotic expansion gives for ph z >= 2π/3. closer to
+// the real axis it misses a multiple of Ai(ω̄z), switched on across the
+// Stokes line ph z = 2π/3, which Hi(z) = ω Hi(ωz) + 2 e^(-πi/6) Ai(ω̄z) adds
+fn hi(z: Complex64) -> Complex64 {
+    if z.im < 0. {
+        return hi(z.conj()).conj();
+    }
+    let r = z.norm();
+    if r <= HI_SERIES_RADIUS {
+        hi_series(z)
+    } else if r <= HI_ASYMPTOTIC_RADIUS {
+        hi_integral(z)
+    } else if z.arg() >= 2. * PI / 3. {
+        hi_asymptotic(z)
+    } else {
+        OMEGA * hi_asymptotic(OMEGA * z) + 2. * cis_pi(-1. / 6.) * airy(OMEGA.conj() * z)[0]
+    }
+}
+
+// Hi(z) = 3^(-2/3)/π Σ Γ((k + 1)/3) (3^(1/3) z)^k / k!, in three interleaved
+// sequences with t_k+3 = t_k z³ / ((k + 2) (k + 3))
+fn hi_series(z: Complex64) -> Complex64 {
+    let z3 = z * z * z;
+    let w = 1.4422495703074083 * z;
+    let mut terms = [
+        Complex64::new(GAMMA_THIRD, 0.),
+        GAMMA_TWO_THIRDS * w,
+        w * w / 2.,
+    ];
+    let mut sum = terms[0] + terms[1] + terms[2];
+    for k in (0..120).step_by(3) {
+        for (j, t) in terms.iter_mut().enumerate() {
+            *t *= z3 / ((k + j + 2) * (k + j + 3)) as f64;
+            sum += *t;
+        }
+        if terms.iter().map(|t| t.norm()).sum::<f64>() < EPS * sum.norm() {
+            break;
+        }
+    }
+    0.15302743219105738 * sum
+}
+
+// the integral along the ray ph t = θ, which may turn by up to π/8 either
+// way: through the saddle point √z when Hi is large, towards Re zt < 0
+// otherwise, so the integrand neither oscillates nor grows too much. it's
+// cut off where the exponent has fallen 40 below its largest value
+fn hi_integral(z: Complex64) -> Complex64 {
+    let phi = z.arg();
+    let theta = if phi <= PI / 4. {
+        phi / 2.
+    } else {
+        (PI - phi).min(PI / 8.)
+    };
+    let ray = Complex64::from_polar(1., theta);
+    let zr = z * ray;
+    let cube = Complex64::from_polar(1. / 3., 3. * theta);
+    // the exponent is at most a s - c s³/3
+    let (a, c) = (zr.re.max(0.), (3. * theta).cos());
+    let peak = 2. / 3. * a.powf(1.5) / c.sqrt();
+    let end = (6. * (40. + peak) / c).cbrt().max((6. * a / c).sqrt());
+    let panels = (end / PANEL_WIDTH).ceil();
+    let h = end / panels;
+    let f = |s: f64| (zr * s - cube * s.powi(3)).exp();
+    let mut sum = Complex64::new(0., 0.);
+    for p in 0..panels as usize {
+        let mid = (p as f64 + 0.5) * h;
+        for &(x, weight) in GAUSS_LEGENDRE.iter() {
+            sum += weight * (f(mid - x * h / 2.) + f(mid + x * h / 2.));
+        }
+    }
+    ray * sum * h / (2. * PI)
+}
+
+// Hi(z) ~ -1/(πz) Σ (3k)! / (k! (3z³)^k), summed up to its smallest term
+fn hi_asymptotic(z: Complex64) -> Complex64 {
+    let z3 = z * z * z;
+    let mut term = Complex64::new(1., 0.);
+    let mut sum = term;
+    for k in 0..200 {
+        let next = term * ((3 * k + 1) * (3 * k + 2)) as f64 / z3;
+        if next.norm() > term.norm() {
+            break;
+        }
+        term = next;
+        sum += term;
+        if term.norm() < EPS * sum.norm() {
+            break;
+        }
+    }
+    -sum / (PI * z)
+}
+
+// Gi(z) = 1/π ∫_0^∞ sin(t³/3 + zt) dt, which is Bi - Hi. for Re z >= 0,
+// where that would cancel, the two halves of the sine turned onto the rays
+// ph t = ±π/6 give Gi(z) = (e^(πi/6) Hi(ωz) - e^(-πi/6) Hi(ω̄z)) / 2i
+fn gi(z: Complex64) -> Complex64 {
+    if z.re >= 0. {
+        (cis_pi(1. / 6.) * hi(OMEGA * z) - cis_pi(-1. / 6.) * hi(OMEGA.conj() * z))
+            / Complex64::new(0., 2.)
+    } else {
+        airy(z)[2] - hi(z)
+    }
+}
+
+pub fn scorer_gi(z: Complex64) -> Complex64 {
+    finish(z, gi(z))
+}
+
+pub fn scorer_hi(z: Complex64) -> Complex64 {
+    finish(z, hi(z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    // (x, y, Re f, Im f) from mpmath at 60 digits: both half-axes and every
+    // Stokes sector, near 0 and far out
+    fn check(f: fn(Complex64) -> Complex64, values: &[(f64, f64, f64, f64)]) {
+        for &(x, y, re, im) in values {
+            let (got, want) = (f(c(x, y)), c(re, im));
+            assert!(
+                (got - want).norm() <= 5e-14 * want.norm(),
+                "at {}: got {}, want {}",
+                c(x, y),
+                got,
+                want
+            );
+        }
+    }
+
+    #[test]
+    fn airy_ai_values() {
+        check(
+            airy_ai,
+            &[
+                (0.5, 0., 0.23169360648083348, 0.),
+                (3., 0., 0.006591139357460719, 0.),
+                (12., 0., 1.3931846888753607e-13, 0.),
+                (-3., 0., -0.37881429367765806, 0.),
+                (-12., 0., -0.06655517505437313, 0.),
+                (-30., 0., -0.08796818845684216, 0.),
+                (0., 1.5, 0.22372785110975563, -0.5781126623007086),
+                (
+                    6.928203230275509,
+                    4.,
+                    -7.784951538302689e-7,
+                    3.8185108922311105e-6,
+                ),
+                (
+                    -5.656854249492381,
+                    5.656854249492381,
+                    86029.66623792422,
+                    -169596.0698655696,
+                ),
+                (
+                    4.,
+                    -6.928203230275509,
+                    -0.15661240457621528,
+                    0.05999093892960032,
+                ),
+                (
+                    -6.928203230275509,
+                    -4.,
+                    -6023.0660536049445,
+                    3980.4330807880256,
+                ),
+                (
+                    -2.2574263096700946,
+                    12.802500789158705,
+                    -79088970950.151,
+                    28984628601.574715,
+                ),
+                (
+                    21.650635094610966,
+                    12.5,
+                    -2.6039896585762934e-27,
+                    -1.9161317091712928e-27,
+                ),
+                (0., 25., -4.585050249001211e24, -1.7920504625684325e24),
+                (
+                    -24.148145657226706,
+                    6.470476127563019,
+                    5750979199109.509,
+                    6830842800860.595,
+                ),
+                (
+                    -12.5,
+                    -21.650635094610966,
+                    1.6982891441041388e35,
+                    9.805076945103404e34,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn airy_bi_values() {
+        check(
+            airy_bi,
+            &[
+                (0.5, 0., 0.8542770431031554, 0.),
+                (3., 0., 14.037328963730232, 0.),
+                (12., 0., 329807225829.07416, 0.),
+                (-3., 0., -0.19828962637492653, 0.),
+                (-12., 0., -0.2957199120780731, 0.),
+                (-30., 0., -0.22444694220056632, 0.),
+                (0., 1.5, 0.7646118577873013, 0.3131746730618384),
+                (
+                    6.928203230275509,
+                    4.,
+                    -6451.823337580583,
+                    -12917.378384879217,
+                ),
+                (
+                    -5.656854249492381,
+                    5.656854249492381,
+                    169596.06986586467,
+                    86029.66623790146,
+                ),
+                (
+                    4.,
+                    -6.928203230275509,
+                    -0.151278763962337,
+                    -0.10390735421982973,
+                ),
+                (
+                    -6.928203230275509,
+                    -4.,
+                    3980.433083258143,
+                    6023.066046212594,
+                ),
+                (
+                    -2.2574263096700946,
+                    12.802500789158705,
+                    -28984628601.574715,
+                    -79088970950.151,
+                ),
+                (
+                    21.650635094610966,
+                    12.5,
+                    -6.149489523957973e24,
+                    7.688972699897027e24,
+                ),
+                (0., 25., 1.7920504625684325e24, -4.585050249001211e24),
+                (
+                    -24.148145657226706,
+                    6.470476127563019,
+                    -6830842800860.595,
+                    5750979199109.509,
+                ),
+                (
+                    -12.5,
+                    -21.650635094610966,
+                    9.805076945103404e34,
+                    -1.6982891441041388e35,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn airy_ai_prime_values() {
+        check(
+            airy_ai_prime,
+            &[
+                (0.5, 0., -0.2249105326646839, 0.),
+                (3., 0., -0.011912976705951319, 0.),
+                (12., 0., -4.854736554985309e-13, 0.),
+                (-3., 0., 0.3145837692165988, 0.),
+                (-12., 0., 1.0231104533679707, 0.),
+                (-30., 0., 1.228620602637485, 0.),
+                (0., 1.5, -0.6110271969857892, 0.3786304810892909),
+                (
+                    6.928203230275509,
+                    4.,
+                    4.886445724971372e-6,
+                    -9.976418043262561e-6,
+                ),
+                (
+                    -5.656854249492381,
+                    5.656854249492381,
+                    -530515.5786857796,
+                    -43201.26393882486,
+                ),
+                (
+                    4.,
+                    -6.928203230275509,
+                    0.3029343254256346,
+                    -0.3652471451808231,
+                ),
+                (
+                    -6.928203230275509,
+                    -4.,
+                    -6572.655048173712,
+                    -19165.62425651142,
+                ),
+                (
+                    -2.2574263096700946,
+                    12.802500789158705,
+                    262540088470.40216,
+                    149846585747.32346,
+                ),
+                (
+                    21.650635094610966,
+                    12.5,
+                    1.0128653283007878e-26,
+                    1.262768941217441e-26,
+                ),
+                (0., 25., 9.892894185708116e24, 2.250050766367497e25),
+                (
+                    -24.148145657226706,
+                    6.470476127563019,
+                    30147071319469.965,
+                    -32886073185395.008,
+                ),
+                (
+                    -12.5,
+                    -21.650635094610966,
+                    -8.474376876407674e35,
+                    4.8926837708083274e35,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn airy_bi_prime_values() {
+        check(
+            airy_bi_prime,
+            &[
+                (0.5, 0., 0.5445725641405923, 0.),
+                (3., 0., 22.92221496638217, 0.),
+                (12., 0., 1135507502443.3708, 0.),
+                (-3., 0., -0.6756112226852585, 0.),
+                (-12., 0., -0.23673219783112331, 0.),
+                (-30., 0., -0.48369472582768147, 0.),
+                (0., 1.5, -0.30338100500391907, -0.34524663424099644),
+                (
+                    6.928203230275509,
+                    4.,
+                    -7781.451782426863,
+                    -39768.490024112405,
+                ),
+                (
+                    -5.656854249492381,
+                    5.656854249492381,
+                    43201.26393921051,
+                    -530515.5786850272,
+                ),
+                (
+                    4.,
+                    -6.928203230275509,
+                    -1.2551919333554507,
+                    -0.632626612772672,
+                ),
+                (
+                    -6.928203230275509,
+                    -4.,
+                    -19165.62427495141,
+                    6572.655035780495,
+                ),
+                (
+                    -2.2574263096700946,
+                    12.802500789158705,
+                    -149846585747.32346,
+                    262540088470.40216,
+                ),
+                (
+                    21.650635094610966,
+                    12.5,
+                    -3.9635503027224025e25,
+                    2.907912828386978e25,
+                ),
+                (0., 25., -2.250050766367497e25, 9.892894185708116e24),
+                (
+                    -24.148145657226706,
+                    6.470476127563019,
+                    32886073185395.008,
+                    30147071319469.965,
+                ),
+                (
+                    -12.5,
+                    -21.650635094610966,
+                    4.8926837708083274e35,
+                    8.474376876407674e35,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn scorer_gi_values() {
+        check(
+            scorer_gi,
+            &[
+                (0.5, 0., 0.2447210432765582, 0.),
+                (3., 0., 0.11422886892313992, 0.),
+                (12., 0., 0.02655689271351241, 0.),
+                (-3., 0., -0.2990547183713964, 0.),
+                (-12., 0., -0.32221537922982774, 0.),
+                (-30., 0., -0.23505648637067258, 0.),
+                (0., 1.5, 0.609329378189722, 0.044074130707697105),
+                (
+                    6.928203230275509,
+                    4.,
+                    0.03437168732659331,
+                    -0.020026234013646168,
+                ),
+                (
+                    -5.656854249492381,
+                    5.656854249492381,
+                    169596.0415714145,
+                    86029.63810804316,
+                ),
+                (
+                    4.,
+                    -6.928203230275509,
+                    0.07981035216616268,
+                    0.19094063527814442,
+                ),
+                (
+                    -6.928203230275509,
+                    -4.,
+                    3980.3985529520232,
+                    6023.085803546159,
+                ),
+                (
+                    -2.2574263096700946,
+                    12.802500789158705,
+                    -28984628601.57895,
+                    -79088970950.17513,
+                ),
+                (
+                    21.650635094610966,
+                    12.5,
+                    0.011025761232316201,
+                    -0.006367608076162266,
+                ),
+                (0., 25., 1.7920504625684325e24, -4.585050249001211e24),
+                (
+                    -24.148145657226706,
+                    6.470476127563019,
+                    -6830842800860.607,
+                    5750979199109.506,
+                ),
+                (
+                    -12.5,
+                    -21.650635094610966,
+                    9.805076945103404e34,
+                    -1.6982891441041388e35,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn scorer_hi_values() {
+        check(
+            scorer_hi,
+            &[
+                (0.5, 0., 0.6095559998265973, 0.),
+                (3., 0., 13.923100094807092, 0.),
+                (12., 0., 329807225829.0476, 0.),
+                (-3., 0., 0.10076509199646988, 0.),
+                (-12., 0., 0.026495467151754687, 0.),
+                (-30., 0., 0.010609544170106257, 0.),
+                (0., 1.5, 0.15528247959757938, 0.26910054235414127),
+                (
+                    6.928203230275509,
+                    4.,
+                    -6451.85770926791,
+                    -12917.358358645204,
+                ),
+                (
+                    -5.656854249492381,
+                    5.656854249492381,
+                    0.02829445017097477,
+                    0.028129858300816508,
+                ),
+                (
+                    4.,
+                    -6.928203230275509,
+                    -0.2310891161284997,
+                    -0.29484798949797414,
+                ),
+                (
+                    -6.928203230275509,
+                    -4.,
+                    0.03453030612011242,
+                    -0.019757333563956578,
+                ),
+                (
+                    -2.2574263096700946,
+                    12.802500789158705,
+                    0.004234574544426625,
+                    0.024127640958446325,
+                ),
+                (
+                    21.650635094610966,
+                    12.5,
+                    -6.149489523957973e24,
+                    7.688972699897027e24,
+                ),
+                (0., 25., -1.6297391413786344e-6, 0.012732393361328581),
+                (
+                    -24.148145657226706,
+                    6.470476127563019,
+                    0.012297734186321851,
+                    0.003293977040821967,
+                ),
+                (
+                    -12.5,
+                    -21.650635094610966,
+                    0.006367013643787156,
+                    -0.011027991123523604,
+                ),
+            ],
+        );
+    }
+}
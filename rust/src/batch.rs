@@ -0,0 +1,209 @@
+use crate::math_tokenizer::MathToken;
+use crate::parser::RPNExpr;
+use crate::rpneval::{div_fallback, MathContext};
+use crate::simd::{C32x4, F32x4, WIDTH};
+use num_complex::Complex32;
+use std::f32::consts::E;
+
+// number of inputs evaluated together
+pub const LANES: usize = 2 * WIDTH;
+
+// LANES complex numbers as 2 SIMD vectors. arithmetic and the elementary
+// functions use the kernels in simd, which MathContext::eval also goes
+// through for a single z, so the results match it bit for bit
+#[derive(Clone, Copy)]
+struct Lanes([C32x4; 2]);
+
+impl Lanes {
+    fn splat(c: Complex32) -> Lanes {
+        Lanes([C32x4::splat(c); 2])
+    }
+
+    // the first LANES of zs
+    fn load(zs: &[Complex32]) -> Lanes {
+        Lanes([C32x4::load(&zs[..WIDTH]), C32x4::load(&zs[WIDTH..LANES])])
+    }
+
+    fn to_array(self) -> [Complex32; LANES] {
+        let mut out = [Complex32::new(0., 0.); LANES];
+        out[..WIDTH].copy_from_slice(&self.0[0].to_array());
+        out[WIDTH..].copy_from_slice(&self.0[1].to_array());
+        out
+    }
+
+    fn map(self, f: impl Fn(C32x4) -> C32x4) -> Lanes {
+        Lanes(self.0.map(f))
+    }
+
+    fn zip(self, o: Lanes, f: impl Fn(C32x4, C32x4) -> C32x4) -> Lanes {
+        Lanes([f(self.0[0], o.0[0]), f(self.0[1], o.0[1])])
+    }
+
+    // like MathContext's "/": div_fallback wherever the plain quotient isn't normal
+    fn div(self, o: Lanes) -> Lanes {
+        let mut out = [Complex32::new(0., 0.); LANES];
+        for h in 0..2 {
+            let (q, bad) = self.0[h].div(o.0[h]);
+            let q = q.to_array();
+            let (l, r) = (self.0[h].to_array(), o.0[h].to_array());
+            for (k, &bad) in bad.to_array().iter().enumerate() {
+                out[h * WIDTH + k] = if bad { div_fallback(l[k], r[k]) } else { q[k] };
+            }
+        }
+        Lanes::load(&out)
+    }
+
+    // falls back to a scalar function per lane
+    fn per_lane(
+        args: &[Lanes],
+        f: impl Fn(Vec<Complex32>) -> Result<Complex32, String>,
+    ) -> Result<Lanes, String> {
+        let args: Vec<_> = args.iter().map(|a| a.to_array()).collect();
+        let mut out = [Complex32::new(0., 0.); LANES];
+        for (k, o) in out.iter_mut().enumerate() {
+            *o = f(args.iter().map(|a| a[k]).collect())?;
+        }
+        Ok(Lanes::load(&out))
+    }
+}
+
+impl MathContext {
+    // evaluates rpn at every z in zs, writing f(z) to the same index of out.
+    // inputs are processed LANES at a time: arithmetic and the elementary
+    // functions run on whole lanes, everything else (powers, special
+    // functions) falls back to the scalar code for each lane. the last
+    // zs.len() % LANES inputs go through eval rather than padded lanes
+    pub fn eval_many(
+        &self,
+        rpn: &RPNExpr,
+        zs: &[Complex32],
+        out: &mut [Complex32],
+    ) -> Result<(), String> {
+        if zs.len() != out.len() {
+            return Err("Input and output lengths differ".to_string());
+        }
+        let mut chunks = zs.chunks_exact(LANES);
+        let mut out_chunks = out.chunks_exact_mut(LANES);
+        for (zs, out) in (&mut chunks).zip(&mut out_chunks) {
+            out.copy_from_slice(&self.eval_lanes(rpn, &Lanes::load(zs))?.to_array());
+        }
+        let rest = chunks.remainder();
+        for (&z, o) in rest.iter().zip(out_chunks.into_remainder()) {
+            *o = self.eval(rpn, z)?;
+        }
+        Ok(())
+    }
+
+    fn eval_lanes(&self, rpn: &RPNExpr, z: &Lanes) -> Result<Lanes, String> {
+        let mut operands = Vec::new();
+
+        for token in rpn.0.iter() {
+            match *token {
+                MathToken::Number(num) => operands.push(Lanes::splat(Complex32::new(num, 0.))),
+                MathToken::Imaginary(num) => operands.push(Lanes::splat(Complex32::new(0., num))),
+                MathToken::Variable(ref name) => match &name[..] {
+                    "e" => operands.push(Lanes::splat(Complex32::new(E, 0.))),
+                    "z" | "x" => operands.push(*z),
+                    _ => operands.push(Lanes::splat(Complex32::new(0., 0.))),
+                },
+                MathToken::BOp(ref op) => {
+                    let r = operands.pop().ok_or("Wrong number of arguments")?;
+                    let l = operands.pop().ok_or("Wrong number of arguments")?;
+                    operands.push(match &op[..] {
+                        "+" => l.zip(r, C32x4::add),
+                        "-" => l.zip(r, C32x4::sub),
+                        "*" => l.zip(r, C32x4::mul),
+                        "/" => l.div(r),
+                        _ => Lanes::per_lane(&[l, r], |a| Self::eval_bop(op, a[0], a[1]))?,
+                    });
+                }
+                MathToken::UOp(ref op) => {
+                    let o = operands.pop().ok_or("Wrong number of arguments")?;
+                    match &op[..] {
+                        "-" => operands.push(o.map(C32x4::neg)),
+                        _ => return Err(format!("Bad Token: {}", op)),
+                    }
+                }
+                MathToken::Function(ref fname, arity) => {
+                    if arity > operands.len() {
+                        return Err("Wrong number of arguments".to_string());
+                    }
+                    let cut = operands.len() - arity;
+                    let args = operands.split_off(cut);
+                    operands.push(eval_fn_lanes(fname, &args)?);
+                }
+                _ => return Err(format!("Bad Token: {:?}", *token)),
+            }
+        }
+        Ok(operands.pop().ok_or("Wrong number of arguments")?)
+    }
+}
+
+// the elementary functions that have a lane-wise version, anything else goes
+// through MathContext::eval_fn one lane at a time
+fn eval_fn_lanes(fname: &str, args: &[Lanes]) -> Result<Lanes, String> {
+    if args.len() != 1 {
+        return Lanes::per_lane(args, |a| MathContext::eval_fn(fname, a));
+    }
+    let z = args[0];
+    Ok(match fname {
+        "sin" => z.map(C32x4::sin),
+        "cos" => z.map(C32x4::cos),
+        "sinh" => z.map(C32x4::sinh),
+        "cosh" => z.map(C32x4::cosh),
+        "exp" => z.map(C32x4::exp),
+        "ln" => z.map(C32x4::ln),
+        "conj" => z.map(C32x4::conj),
+        "Re" => z.map(|z| C32x4 {
+            re: z.re,
+            im: F32x4::splat(0.),
+        }),
+        "Im" => z.map(|z| C32x4 {
+            re: z.im,
+            im: F32x4::splat(0.),
+        }),
+        "norm" | "mod" => z.map(C32x4::norm),
+        "arg" => z.map(C32x4::arg),
+        _ => Lanes::per_lane(args, |a| MathContext::eval_fn(fname, a))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ShuntingParser;
+
+    #[test]
+    fn eval_many_matches_eval() {
+        let ctx = MathContext::new();
+        // a spiral through the plane, with 0, the real axis and a length
+        // that leaves a partial chunk
+        let mut zs: Vec<Complex32> = (0..203)
+            .map(|k| Complex32::from_polar(k as f32 / 10., k as f32 * 0.7))
+            .collect();
+        zs.extend([Complex32::new(-2., 0.), Complex32::new(1., 0.)]);
+        for input in [
+            "z^2 + 3*z - 1/z",
+            "(z - 1) / (z + 1)",
+            "sin(z) * cos(z) - exp(z) / ln(z)",
+            "sinh(z) + cosh(conj(z)) + Re(z) * Im(z)",
+            "norm(z) + arg(z) * i",
+            "-z^(1/3) + gamma(z)",
+            "1/0 + z",
+        ] {
+            let expr = ShuntingParser::parse_str(input).unwrap();
+            let mut out = vec![Complex32::new(0., 0.); zs.len()];
+            ctx.eval_many(&expr, &zs, &mut out).unwrap();
+            for (&z, w) in zs.iter().zip(&out) {
+                let want = ctx.eval(&expr, z).unwrap();
+                assert_eq!(
+                    (w.re.to_bits(), w.im.to_bits()),
+                    (want.re.to_bits(), want.im.to_bits()),
+                    "{} at {}",
+                    input,
+                    z
+                );
+            }
+        }
+    }
+}
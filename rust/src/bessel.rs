@@ -0,0 +1,803 @@
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+// beyond this |z| (and ν²) the Hankel expansions are used
+const ASYMPTOTIC_RADIUS: f64 = 20.;
+// below this |z| Temme's series gives K, above it Steed's continued fraction
+const TEMME_RADIUS: f64 = 2.;
+const EPS: f64 = 1e-16;
+const MAX_TERMS: usize = 100_000;
+// the Taylor coefficients of 1/Γ(1 + z) = (1/Γ(z)) / z
+static RGAMMA_TAYLOR: [f64; 26] = [
+    1.0,
+    0.5772156649015329,
+    -0.6558780715202539,
+    -0.04200263503409524,
+    0.16653861138229148,
+    -0.04219773455554433,
+    -0.009621971527876973,
+    0.0072189432466631,
+    -0.0011651675918590652,
+    -0.00021524167411495098,
+    0.0001280502823881162,
+    -2.013485478078824e-05,
+    -1.2504934821426706e-06,
+    1.133027231981696e-06,
+    -2.056338416977607e-07,
+    6.116095104481416e-09,
+    5.002007644469223e-09,
+    -1.18127457048702e-09,
+    1.0434267116911005e-10,
+    7.782263439905071e-12,
+    -3.696805618642206e-12,
+    5.100370287454476e-13,
+    -2.0583260535665066e-14,
+    -5.348122539423018e-15,
+    1.2267786282382608e-15,
+    -1.1812593016974588e-16,
+];
+
+fn infinity() -> Complex64 {
+    Complex64::new(f64::INFINITY, 0.)
+}
+
+fn nan() -> Complex64 {
+    Complex64::new(f64::NAN, f64::NAN)
+}
+
+fn real_if(real: bool, w: Complex64) -> Complex64 {
+    if real {
+        Complex64::new(w.re, 0.)
+    } else {
+        w
+    }
+}
+
+// cos(πx) and sin(πx), exact at the integers and half-integers
+fn cos_pi(x: f64) -> f64 {
+    let r = x.rem_euclid(2.);
+    if r == 0.5 || r == 1.5 {
+        0.
+    } else {
+        (PI * r).cos()
+    }
+}
+
+fn sin_pi(x: f64) -> f64 {
+    let r = x.rem_euclid(2.);
+    if r == 0. || r == 1. {
+        0.
+    } else {
+        (PI * r).sin()
+    }
+}
+
+// e^(iπx)
+fn cis_pi(x: f64) -> Complex64 {
+    Complex64::new(cos_pi(x), sin_pi(x))
+}
+
+// --------------------------------------------------------------
+// modified bessel functions, the core
+// --------------------------------------------------------------
+// I_ν(w) and K_ν(w) for ν >= 0 and Re w >= 0, w != 0, after Numerical
+// Recipes' bessik taken to complex w: the continued fraction CF1 gives
+// I'_ν/I_ν, recurred down to the order μ = ν - round(ν) in [-1/2, 1/2].
+// K_μ and K_μ+1 come from Temme's series or Steed's CF2, the Wronskian
+// I_μ K'_μ - I'_μ K_μ = -1/w fixes I_μ, and K recurs up to ν
+fn bessel_ik(nu: f64, w: Complex64) -> (Complex64, Complex64) {
+    if w.norm() > ASYMPTOTIC_RADIUS.max(nu * nu) {
+        return bessel_ik_asymptotic(nu, w);
+    }
+    let tiny = 1e-150;
+    let guard = |x: Complex64| {
+        if x.norm() < tiny {
+            Complex64::new(tiny, 0.)
+        } else {
+            x
+        }
+    };
+    let nl = (nu + 0.5).floor() as usize;
+    let mu = nu - nl as f64;
+    let xi = w.inv();
+    let xi2 = 2. * xi;
+
+    // CF1 by the modified Lentz method
+    let mut h = guard(nu * xi);
+    let mut b = xi2 * nu;
+    let mut d = Complex64::new(0., 0.);
+    let mut c = h;
+    for _ in 0..MAX_TERMS {
+        b += xi2;
+        d = guard(b + d).inv();
+        c = guard(b + c.inv());
+        let delta = c * d;
+        h *= delta;
+        if (delta - 1.).norm() < EPS {
+            break;
+        }
+    }
+
+    // I_l and I'_l from l = ν down to μ, in arbitrary scale
+    let (mut il, mut ipl) = (Complex64::new(1., 0.), h);
+    let mut top = Complex64::new(1., 0.);
+    let mut fact = nu * xi;
+    for _ in 0..nl {
+        let next = fact * il + ipl;
+        fact -= xi;
+        ipl = fact * next + il;
+        il = next;
+        // kept well below where the squares in complex division overflow
+        let scale = il.norm();
+        if scale > 1e100 {
+            il /= scale;
+            ipl /= scale;
+            top /= scale;
+        }
+    }
+    let scale = il.norm();
+    let (il, ipl, top) = (il / scale, ipl / scale, top / scale);
+    let f = ipl / il;
+
+    let (mut kmu, mut k1) = if w.norm() < TEMME_RADIUS {
+        temme_k(mu, w)
+    } else {
+        steed_k(mu, w)
+    };
+    let kmup = mu * xi * kmu - k1;
+    let imu = xi / (f * kmu - kmup);
+    let i_nu = imu * top / il;
+    for i in 1..=nl {
+        let next = (mu + i as f64) * xi2 * k1 + kmu;
+        kmu = k1;
+        k1 = next;
+    }
+    (i_nu, kmu)
+}
+
+// K_μ(w) and K_μ+1(w) for |μ| <= 1/2 and small |w| from Temme's series
+fn temme_k(mu: f64, w: Complex64) -> (Complex64, Complex64) {
+    let x2 = w / 2.;
+    let pimu = PI * mu;
+    let fact = if pimu.abs() < EPS {
+        1.
+    } else {
+        pimu / pimu.sin()
+    };
+    let d = -x2.ln();
+    let e = mu * d;
+    let fact2 = if e.norm() < EPS {
+        Complex64::new(1., 0.)
+    } else {
+        e.sinh() / e
+    };
+    // 1/Γ(1 + μ) = Σ c_k μ^k, 1/Γ(1 - μ), and from them
+    // (1/Γ(1 - μ) - 1/Γ(1 + μ)) / 2μ = -Σ_odd c_k μ^(k-1) and the mean
+    let (mut gampl, mut gammi, mut gam1, mut gam2) = (0., 0., 0., 0.);
+    let (mut power, mut below) = (1., 0.);
+    for (k, c) in RGAMMA_TAYLOR.iter().enumerate() {
+        gampl += c * power;
+        if k % 2 == 0 {
+            gammi += c * power;
+            gam2 += c * power;
+        } else {
+            gammi -= c * power;
+            gam1 -= c * below;
+        }
+        below = power;
+        power *= mu;
+    }
+    let mut ff = fact * (gam1 * e.cosh() + gam2 * fact2 * d);
+    let mut sum = ff;
+    let ee = e.exp();
+    let mut p = 0.5 * ee / gampl;
+    let mut q = 0.5 / (ee * gammi);
+    let mut c = Complex64::new(1., 0.);
+    let dd = x2 * x2;
+    let mut sum1 = p;
+    for i in 1..MAX_TERMS {
+        let i = i as f64;
+        ff = (i * ff + p + q) / (i * i - mu * mu);
+        c *= dd / i;
+        p /= i - mu;
+        q /= i + mu;
+        let del = c * ff;
+        sum += del;
+        sum1 += c * (p - i * ff);
+        if del.norm() < sum.norm() * EPS {
+            break;
+        }
+    }
+    (sum, sum1 * 2. / w)
+}
+
+// K_μ(w) and K_μ+1(w) for |μ| <= 1/2 and |w| >= 2, Re w >= 0 from Steed's
+// continued fraction CF2
+fn steed_k(mu: f64, w: Complex64) -> (Complex64, Complex64) {
+    let mut b = 2. * (1. + w);
+    let mut d = b.inv();
+    let mut h = d;
+    let mut delh = d;
+    let mut q1 = Complex64::new(0., 0.);
+    let mut q2 = Complex64::new(1., 0.);
+    let a1 = 0.25 - mu * mu;
+    let mut q = Complex64::new(a1, 0.);
+    let mut c = a1;
+    let mut a = -a1;
+    let mut s = 1. + q * delh;
+    for i in 2..MAX_TERMS {
+        let i = i as f64;
+        a -= 2. * (i - 1.);
+        c = -a * c / i;
+        let qnew = (q1 - b * q2) / a;
+        q1 = q2;
+        q2 = qnew;
+        q += c * qnew;
+        b += 2.;
+        d = (b + a * d).inv();
+        delh *= b * d - 1.;
+        h += delh;
+        let dels = q * delh;
+        s += dels;
+        if (dels / s).norm() < EPS {
+            break;
+        }
+    }
+    h *= a1;
+    let kmu = (PI / (2. * w)).sqrt() * (-w).exp() / s;
+    let k1 = kmu * (mu + w + 0.5 - h) / w;
+    (kmu, k1)
+}
+
+// the Hankel expansions for large |w|, Re w >= 0:
+// K_ν(w) ~ √(π/2w) e^-w Σ a_k(ν) w^-k and
+// I_ν(w) ~ (e^w Σ (-1)^k a_k(ν) w^-k ± i e^(±iνπ) e^-w Σ a_k(ν) w^-k) / √(2πw)
+// for ±Im w >= 0, a_k(ν) = (4ν² - 1²)(4ν² - 3²)...(4ν² - (2k-1)²) / (k! 8^k)
+fn bessel_ik_asymptotic(nu: f64, w: Complex64) -> (Complex64, Complex64) {
+    let mut term = Complex64::new(1., 0.);
+    let mut sum_k = Complex64::new(0., 0.);
+    let mut sum_i = Complex64::new(0., 0.);
+    for k in 0..MAX_TERMS {
+        sum_k += term;
+        sum_i += if k % 2 == 0 { term } else { -term };
+        let kf = k as f64;
+        let next = term * (4. * nu * nu - (2. * kf + 1.).powi(2)) / (8. * (kf + 1.) * w);
+        if next.norm() < EPS * sum_k.norm().min(sum_i.norm()) || next.norm() > term.norm() {
+            break;
+        }
+        term = next;
+    }
+    let k = (PI / (2. * w)).sqrt() * (-w).exp() * sum_k;
+    let sign = if w.im >= 0. { 1. } else { -1. };
+    let other = Complex64::new(0., sign) * cis_pi(sign * nu) * (-w).exp() * sum_k;
+    let i = (w.exp() * sum_i + other) / (2. * PI * w).sqrt();
+    (i, k)
+}
+
+// --------------------------------------------------------------
+// continuation to the whole plane and all real orders
+// --------------------------------------------------------------
+// I_ν(z) and K_ν(z) for ν >= 0, z != 0, with I_ν(z e^(±iπ)) = e^(±iνπ) I_ν(z)
+// and K_ν(z e^(±iπ)) = e^(∓iνπ) K_ν(z) ∓ iπ I_ν(z) in the left half-plane
+pub(crate) fn ik(nu: f64, z: Complex64) -> (Complex64, Complex64) {
+    if z.re >= 0. {
+        return bessel_ik(nu, z);
+    }
+    let (i, k) = bessel_ik(nu, -z);
+    let sign = if z.im >= 0. { 1. } else { -1. };
+    (
+        cis_pi(sign * nu) * i,
+        cis_pi(-sign * nu) * k - Complex64::new(0., sign * PI) * i,
+    )
+}
+
+// J_ν, Y_ν, H⁽¹⁾_ν, H⁽²⁾_ν for ν >= 0, z != 0. in the first quadrant
+// J_ν(z) = e^(iνπ/2) I_ν(-iz) and H⁽¹⁾_ν(z) = 2/(πi) e^(-iνπ/2) K_ν(-iz),
+// which is the small one there, and Y = i (J - H⁽¹⁾), H⁽²⁾ = 2J - H⁽¹⁾. the
+// lower half-plane is the mirror image, the upper left quadrant comes from
+// z = -z e^(iπ) in the lower right one
+fn jy(nu: f64, z: Complex64) -> [Complex64; 4] {
+    let i = Complex64::i();
+    if z.im < 0. {
+        let [j, y, h1, h2] = jy(nu, z.conj());
+        return [j.conj(), y.conj(), h2.conj(), h1.conj()];
+    }
+    if z.re >= 0. {
+        let (bi, bk) = bessel_ik(nu, -i * z);
+        let j = cis_pi(nu / 2.) * bi;
+        let h1 = 2. / (PI * i) * cis_pi(-nu / 2.) * bk;
+        return [j, i * (j - h1), h1, 2. * j - h1];
+    }
+    let [jw, yw, _, h2w] = jy(nu, -z);
+    let j = cis_pi(nu) * jw;
+    let y = cis_pi(-nu) * yw + 2. * i * cos_pi(nu) * jw;
+    let h1 = -cis_pi(-nu) * h2w;
+    [j, y, h1, 2. * j - h1]
+}
+
+// the same for any real ν, from J_-ν = cos(νπ) J_ν - sin(νπ) Y_ν,
+// Y_-ν = sin(νπ) J_ν + cos(νπ) Y_ν and H⁽¹˒²⁾_-ν = e^(±iνπ) H⁽¹˒²⁾_ν
+fn jy_any(nu: f64, z: Complex64) -> [Complex64; 4] {
+    if nu >= 0. {
+        return jy(nu, z);
+    }
+    let nu = -nu;
+    let [j, y, h1, h2] = jy(nu, z);
+    let (c, s) = (cos_pi(nu), sin_pi(nu));
+    [
+        c * j - s * y,
+        s * j + c * y,
+        cis_pi(nu) * h1,
+        cis_pi(-nu) * h2,
+    ]
+}
+
+// and with I_-ν = I_ν + 2/π sin(νπ) K_ν, K_-ν = K_ν
+fn ik_any(nu: f64, z: Complex64) -> (Complex64, Complex64) {
+    let (i, k) = ik(nu.abs(), z);
+    if nu >= 0. {
+        (i, k)
+    } else {
+        (i + 2. / PI * sin_pi(-nu) * k, k)
+    }
+}
+
+// J_ν(0) and I_ν(0): 1 for ν = 0, 0 for other integers and ν > 0, infinite
+// otherwise
+fn regular_at_zero(nu: f64) -> Complex64 {
+    if nu == 0. {
+        Complex64::new(1., 0.)
+    } else if nu > 0. || nu == nu.floor() {
+        Complex64::new(0., 0.)
+    } else {
+        infinity()
+    }
+}
+
+// which of the functions, so the spherical ones can share the code
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    J,
+    Y,
+    I,
+    K,
+    H1,
+    H2,
+}
+
+// the bessel functions of real order ν, with the principal branch of z^ν:
+// cut along the negative real axis, where they take the value from above.
+// NaN for complex ν
+fn bessel(kind: Kind, nu: Complex64, z: Complex64) -> Complex64 {
+    if nu.im != 0. || !nu.re.is_finite() || z.is_nan() {
+        return nan();
+    }
+    let nu = nu.re;
+    // -0i is on the cut too, not below it
+    let z = Complex64::new(z.re, z.im + 0.);
+    if z == Complex64::new(0., 0.) {
+        return match kind {
+            Kind::J | Kind::I => regular_at_zero(nu),
+            _ => infinity(),
+        };
+    }
+    let w = match kind {
+        Kind::I | Kind::K => {
+            let (i, k) = ik_any(nu, z);
+            if kind == Kind::I {
+                i
+            } else {
+                k
+            }
+        }
+        _ => {
+            let [j, y, h1, h2] = jy_any(nu, z);
+            match kind {
+                Kind::J => j,
+                Kind::Y => y,
+                Kind::H1 => h1,
+                _ => h2,
+            }
+        }
+    };
+    if w.re.is_infinite() || w.im.is_infinite() {
+        return infinity();
+    }
+    // all real on the positive axis, J and I on the whole axis for integers
+    let integer = nu == nu.floor();
+    let real = z.im == 0.
+        && match kind {
+            Kind::J | Kind::I => z.re > 0. || integer,
+            Kind::Y | Kind::K => z.re > 0.,
+            _ => false,
+        };
+    real_if(real, w)
+}
+
+pub fn bessel_j(nu: Complex64, z: Complex64) -> Complex64 {
+    bessel(Kind::J, nu, z)
+}
+
+pub fn bessel_y(nu: Complex64, z: Complex64) -> Complex64 {
+    bessel(Kind::Y, nu, z)
+}
+
+pub fn bessel_i(nu: Complex64, z: Complex64) -> Complex64 {
+    bessel(Kind::I, nu, z)
+}
+
+pub fn bessel_k(nu: Complex64, z: Complex64) -> Complex64 {
+    bessel(Kind::K, nu, z)
+}
+
+pub fn hankel1(nu: Complex64, z: Complex64) -> Complex64 {
+    bessel(Kind::H1, nu, z)
+}
+
+pub fn hankel2(nu: Complex64, z: Complex64) -> Complex64 {
+    bessel(Kind::H2, nu, z)
+}
+
+// --------------------------------------------------------------
+// spherical bessel functions
+// --------------------------------------------------------------
+// j_n, y_n, h⁽¹˒²⁾_n = √(π/2z) of the order n + 1/2 function, likewise
+// i_n = √(π/2z) I_n+1/2 and k_n = √(π/2z) K_n+1/2 (as in the DLMF). for
+// integer n they're single valued, and real on the real axis but for h⁽¹˒²⁾
+fn spherical(kind: Kind, n: Complex64, z: Complex64) -> Complex64 {
+    if n.im != 0. || !n.re.is_finite() || z.is_nan() {
+        return nan();
+    }
+    let n = n.re;
+    let z = Complex64::new(z.re, z.im + 0.);
+    if z == Complex64::new(0., 0.) {
+        return match kind {
+            Kind::J | Kind::I if n == 0. => Complex64::new(1., 0.),
+            Kind::J | Kind::I if n > 0. => Complex64::new(0., 0.),
+            _ => infinity(),
+        };
+    }
+    let w = (PI / 2.).sqrt() / z.sqrt() * bessel(kind, Complex64::new(n + 0.5, 0.), z);
+    if w.re.is_infinite() || w.im.is_infinite() {
+        return infinity();
+    }
+    let integer = n == n.floor();
+    let real = z.im == 0. && (z.re > 0. || integer) && kind != Kind::H1 && kind != Kind::H2;
+    real_if(real, w)
+}
+
+pub fn spherical_j(n: Complex64, z: Complex64) -> Complex64 {
+    spherical(Kind::J, n, z)
+}
+
+pub fn spherical_y(n: Complex64, z: Complex64) -> Complex64 {
+    spherical(Kind::Y, n, z)
+}
+
+pub fn spherical_i(n: Complex64, z: Complex64) -> Complex64 {
+    spherical(Kind::I, n, z)
+}
+
+pub fn spherical_k(n: Complex64, z: Complex64) -> Complex64 {
+    spherical(Kind::K, n, z)
+}
+
+pub fn spherical_h1(n: Complex64, z: Complex64) -> Complex64 {
+    spherical(Kind::H1, n, z)
+}
+
+pub fn spherical_h2(n: Complex64, z: Complex64) -> Complex64 {
+    spherical(Kind::H2, n, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    // (ν, x, y, Re f, Im f) from mpmath at 60 digits, for integer and real
+    // orders. on the negative real axis the value from above, which pins
+    // the cut
+    fn check(f: fn(Complex64, Complex64) -> Complex64, values: &[(f64, f64, f64, f64, f64)]) {
+        for &(nu, x, y, re, im) in values {
+            let (got, want) = (f(c(nu, 0.), c(x, y)), c(re, im));
+            assert!(
+                (got - want).norm() <= 1e-13 * want.norm(),
+                "order {} at {}: got {}, want {}",
+                nu,
+                c(x, y),
+                got,
+                want
+            );
+        }
+    }
+
+    #[test]
+    fn bessel_j_values() {
+        check(
+            bessel_j,
+            &[
+                (0., 2.5, 0., -0.048383776468198, 0.),
+                (0., 20., 5., 11.57305816151333, -6.048023313832592),
+                (1., -2.5, 0., -0.49709410246427405, 0.),
+                (1., -2., -1.5, -1.0991874287695151, 0.15226199452092193),
+                (1., -15., 30., -586187811279.4136, -432941951306.7636),
+                (3., -2.5, 0., -0.21660039103911352, 0.),
+                (3., 1., 2., -0.2810396668457679, 0.01717506200339023),
+                (3., -40., 0., 0.1261448155058208, 0.),
+                (2.3, -2.5, 0., 0.22124872049434138, 0.3045227388157911),
+                (2.3, 0.1, 0., 0.00037897495018145746, 0.),
+                (2.3, -3., 1., 0.42819455825250535, 0.3865326203371128),
+                (-1.7, -2.5, 0., 0.05760969367636556, 0.07929294082003208),
+                (-1.7, 4., -3., 3.059481577362474, 0.5009927342794401),
+                (-1.7, 0., -8., -314.15501197892684, 160.06997368949038),
+            ],
+        );
+    }
+
+    #[test]
+    fn bessel_y_values() {
+        check(
+            bessel_y,
+            &[
+                (0., 2.5, 0., 0.4980703596152319, 0.),
+                (0., 20., 5., 6.048300582134626, 11.57190897325237),
+                (1., -2.5, 0., -0.1459181379667858, -0.9941882049285481),
+                (1., -2., -1.5, 0.22014722066040993, 1.202307439501331),
+                (1., -15., 30., 432941951306.7636, -586187811279.4136),
+                (3., -2.5, 0., 0.756055496753671, -0.43320078207822704),
+                (3., 1., 2., 0.2901532942395458, -0.21211877047925778),
+                (3., -40., 0., 0.006829103413384208, 0.2522896310116416),
+                (2.3, -2.5, 0., -0.2939999370151663, 0.8471536389160209),
+                (2.3, 0.1, 0., -365.6118542883514, 0.),
+                (2.3, -3., 1., -0.374433821022418, 0.6606736002803971),
+                (-1.7, -2.5, 0., -0.32300447185172493, 0.5597969026407855),
+                (-1.7, 4., -3., 0.4903665390156433, -3.041467370690106),
+                (-1.7, 0., -8., 160.07007218104937, 314.1550621628827),
+            ],
+        );
+    }
+
+    #[test]
+    fn bessel_i_values() {
+        check(
+            bessel_i,
+            &[
+                (0., 2.5, 0., 3.289839144050123, 0.),
+                (0., 20., 5., 6983902.3652421655, -42313571.09064533),
+                (1., -2.5, 0., -2.5167162452886984, 0.),
+                (1., -2., -1.5, -0.3982073219923142, -1.6112538418514901),
+                (1., -15., 30., 84898.54736394617, -207379.77802652234),
+                (3., -2.5, 0., -0.4743704087780356, 0.),
+                (3., 1., 2., -0.1753534440106613, -0.08243079895435534),
+                (3., -40., 0., -1.329145566473366e16, 0.),
+                (2.3, -2.5, 0., 0.5739948127506345, 0.790036082714209),
+                (2.3, 0.1, 0., 0.0003795495898842524, 0.),
+                (2.3, -3., 1., 1.8806721558398085, -0.378011655286261),
+                (-1.7, -2.5, 0., 0.9232218997124534, 1.2707059313472722),
+                (-1.7, 4., -3., -7.5366758788607555, -2.1372392297513114),
+                (-1.7, 0., -8., 0.206022214199113, -0.10497356129564056),
+            ],
+        );
+    }
+
+    #[test]
+    fn bessel_k_values() {
+        check(
+            bessel_k,
+            &[
+                (0., 2.5, 0., 0.06234755320036619, 0.),
+                (0., 20., 5., 2.248086517603596e-10, 5.190928383904736e-10),
+                (1., -2.5, 0., -0.07389081634774707, -7.906497267369063),
+                (1., -2., -1.5, -5.024278365838572, 1.364729983474603),
+                (1., -15., 30., 651502.7871511608, 266716.6526990685),
+                (3., -2.5, 0., -0.2682271463934492, -1.4902785912974639),
+                (3., 1., 2., -0.6814364279663891, 0.62515465459068),
+                (3., -40., 0., -9.3789037246453e-19, -4.1756339471841704e16),
+                (2.3, -2.5, 0., 0.08793627864197273, -3.188919462454996),
+                (2.3, 0.1, 0., 572.0968669282897, 0.),
+                (2.3, -3., 1., -5.415455067628236, -2.485225119322452),
+                (-1.7, -2.5, 0., 0.05952475012204253, -5.01636211644412),
+                (-1.7, 4., -3., -0.011953875442954107, -0.003906627451749619),
+                (-1.7, 0., -8., -0.3981075188239525, 0.20478973829599398),
+            ],
+        );
+    }
+
+    #[test]
+    fn hankel1_values() {
+        check(
+            hankel1,
+            &[
+                (0., 2.5, 0., -0.048383776468198, 0.4980703596152319),
+                (0., 20., 5., 0.0011491882609597792, 0.00027726830203516825),
+                (1., -2.5, 0., 0.49709410246427405, -0.1459181379667858),
+                (1., -2., -1.5, -2.3014948682708463, 0.37240921518133185),
+                (
+                    1.,
+                    -15.,
+                    30.,
+                    1.1600898846099179e-14,
+                    5.9113882948686155e-15,
+                ),
+                (3., -2.5, 0., 0.21660039103911352, 0.756055496753671),
+                (3., 1., 2., -0.06892089636651014, 0.30732835624293603),
+                (3., -40., 0., -0.1261448155058208, 0.006829103413384208),
+                (2.3, -2.5, 0., -0.6259049184216795, 0.01052280180062482),
+                (2.3, 0.1, 0., 0.00037897495018145746, -365.6118542883514),
+                (2.3, -3., 1., -0.23247904202789174, 0.01209879931469479),
+                (-1.7, -2.5, 0., -0.5021872089644199, -0.24371153103169282),
+                (-1.7, 4., -3., 6.10094894805258, 0.9913592732950833),
+                (-1.7, 0., -8., -628.3100741418095, 320.1400458705397),
+            ],
+        );
+    }
+
+    #[test]
+    fn hankel2_values() {
+        check(
+            hankel2,
+            &[
+                (0., 2.5, 0., -0.048383776468198, -0.4980703596152319),
+                (0., 20., 5., 23.1449671347657, -12.096323895967217),
+                (1., -2.5, 0., -1.491282307392822, 0.1459181379667858),
+                (1., -2., -1.5, 0.10312001073181577, -0.06788522613948798),
+                (1., -15., 30., -1172375622558.8271, -865883902613.5272),
+                (3., -2.5, 0., -0.6498011731173405, -0.756055496753671),
+                (3., 1., 2., -0.4931584373250257, -0.2729782322361555),
+                (3., -40., 0., 0.3784344465174624, -0.006829103413384208),
+                (2.3, -2.5, 0., 1.0684023594103622, 0.5985226758309574),
+                (2.3, 0.1, 0., 0.00037897495018145746, 365.6118542883514),
+                (2.3, -3., 1., 1.0888681585329025, 0.7609664413595307),
+                (-1.7, -2.5, 0., 0.617406596317151, 0.40229741267175695),
+                (-1.7, 4., -3., 0.018014206672367694, 0.010626195263796776),
+                (-1.7, 0., -8., 5.018395585983675e-5, -9.849155897832235e-5),
+            ],
+        );
+    }
+
+    #[test]
+    fn spherical_j_values() {
+        check(
+            spherical_j,
+            &[
+                (0., 2.5, 0., 0.2393888576415826, 0.),
+                (0., -3., 1., -0.051016170883679386, 0.3708080642731904),
+                (0., 0., -8., 186.30985322369378, 0.),
+                (2., -2.5, 0., 0.26006672948890525, 0.),
+                (2., 0.1, 0., 0.0006661906084455688, 0.),
+                (2., 20., 5., -3.483890641498646, -0.11326866064898419),
+                (2., -40., 0., -0.01734239296698826, 0.),
+                (-1., 1., 2., -0.8142145182567869, -1.4234687626382263),
+                (-1., -2., -1.5, 0.7779389013694202, 0.3846199887281884),
+                (1.5, -2.5, 0., 0., -0.353575608531634),
+                (1.5, -15., 30., -53431298076.96243, -141721470666.11978),
+            ],
+        );
+    }
+
+    #[test]
+    fn spherical_y_values() {
+        check(
+            spherical_y,
+            &[
+                (0., 2.5, 0., 0.3204574462187735, 0.),
+                (0., -3., 1., -0.4748759152268609, -0.10301050443596069),
+                (0., 0., -8., 0., -186.30989515652226),
+                (2., -2.5, 0., 0.4539045012036613, 0.),
+                (2., 0.1, 0., -3005.012479175345, 0.),
+                (2., 20., 5., 0.11343913426575176, -3.4835968854729806),
+                (2., -40., 0., 0.018039275995565374, 0.),
+                (-1., 1., 2., 1.416996119211876, -0.8743911970021461),
+                (-1., -2., -1.5, 0.471830504946809, -0.7969193333913724),
+                (1.5, -2.5, 0., 0.707151217063268, 0.30227175617116187),
+                (1.5, -15., 30., 141721470666.11978, -53431298076.96243),
+            ],
+        );
+    }
+
+    #[test]
+    fn spherical_i_values() {
+        check(
+            spherical_i,
+            &[
+                (0., 2.5, 0., 2.420081792415915, 0.),
+                (0., -3., 1., 2.470968822383473, -2.0002255439722254),
+                (0., 0., -8., 0.12366978082792272, 0.),
+                (2., -2.5, 0., 0.6382221025369847, 0.),
+                (2., 0.1, 0., 0.0006671429894380332, 0.),
+                (2., 20., 5., 809701.4731215443, -10153769.146269469),
+                (2., -40., 0., 2727158989994536., 0.),
+                (-1., 1., 2., 0.29901334361000736, 0.47058073416276364),
+                (-1., -2., -1.5, -0.9534267202459676, -1.093817496785593),
+                (1.5, -2.5, 0., 0., -1.0118106256243367),
+                (1.5, -15., 30., 29499.055403956587, 37296.91900215168),
+            ],
+        );
+    }
+
+    #[test]
+    fn spherical_k_values() {
+        check(
+            spherical_k,
+            &[
+                (0., 2.5, 0., 0.05157552572935375, 0.),
+                (0., -3., 1., -7.768873105183268, 6.259926163311498),
+                (0., 0., -8., -0.19426003746003095, -0.028568864831887926),
+                (2., -2.5, 0., -2.143256277643491, 0.),
+                (2., 0.1, 0., 4704.553618497604, 0.),
+                (2., 20., 5., 9.672357097347188e-11, 1.5216116858466583e-10),
+                (2., -40., 0., -8567622648138195., 0.),
+                (-1., 1., 2., -0.2582752091080404, -0.008899534422394016),
+                (-1., -2., -1.5, -3.0413587386852425, -3.507794563106741),
+                (1.5, -2.5, 0., -3.1786968282855086, -0.09627731022333781),
+                (1.5, -15., 30., 117171.7267386895, -92674.01574492251),
+            ],
+        );
+    }
+
+    #[test]
+    fn spherical_h1_values() {
+        check(
+            spherical_h1,
+            &[
+                (0., 2.5, 0., 0.2393888576415826, 0.3204574462187735),
+                (0., -3., 1., 0.05199433355228131, -0.10406785095367052),
+                (0., 0., -8., 372.61974838021604, 0.),
+                (2., -2.5, 0., 0.26006672948890525, 0.4539045012036613),
+                (2., 0.1, 0., 0.0006661906084455688, -3005.012479175345),
+                (2., 20., 5., -0.00029375602566522466, 0.00017047361676756915),
+                (2., -40., 0., -0.01734239296698826, 0.018039275995565374),
+                (-1., 1., 2., 0.06017667874535916, -0.006472643426350235),
+                (-1., -2., -1.5, 1.5748582347607927, 0.8564504936749975),
+                (1.5, -2.5, 0., -0.30227175617116187, 0.353575608531634),
+                (
+                    1.5,
+                    -15.,
+                    30.,
+                    -1.5706157310371971e-15,
+                    -2.4752304616280536e-15,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn spherical_h2_values() {
+        check(
+            spherical_h2,
+            &[
+                (0., 2.5, 0., 0.2393888576415826, -0.3204574462187735),
+                (0., -3., 1., -0.15402667531964007, 0.8456839795000513),
+                (0., 0., -8., -4.193282848781398e-5, 0.),
+                (2., -2.5, 0., 0.26006672948890525, -0.4539045012036613),
+                (2., 0.1, 0., 0.0006661906084455688, 3005.012479175345),
+                (2., 20., 5., -6.9674875269716265, -0.22670779491473597),
+                (2., -40., 0., -0.01734239296698826, -0.018039275995565374),
+                (-1., 1., 2., -1.688605715258933, -2.840464881850102),
+                (-1., -2., -1.5, -0.018980432021952258, -0.08721051621862057),
+                (1.5, -2.5, 0., 0.30227175617116187, -1.0607268255949018),
+                (1.5, -15., 30., -106862596153.92487, -283442941332.23956),
+            ],
+        );
+    }
+
+    #[test]
+    fn negative_zero_is_above() {
+        for f in [
+            bessel_j,
+            bessel_y,
+            bessel_k,
+            hankel1,
+            spherical_y,
+            spherical_h2,
+        ] {
+            for nu in [1., 2.3] {
+                assert_eq!(f(c(nu, 0.), c(-2.5, -0.)), f(c(nu, 0.), c(-2.5, 0.)));
+            }
+        }
+    }
+}
@@ -0,0 +1,481 @@
+use colorous::Gradient;
+use num_complex::Complex32;
+use std::f32::consts::PI;
+
+// every colorous gradient that can be picked by name
+static GRADIENTS: &[(&str, Gradient)] = &[
+    // sequential (multi-hue)
+    ("turbo", colorous::TURBO),
+    ("viridis", colorous::VIRIDIS),
+    ("inferno", colorous::INFERNO),
+    ("magma", colorous::MAGMA),
+    ("plasma", colorous::PLASMA),
+    ("cividis", colorous::CIVIDIS),
+    ("warm", colorous::WARM),
+    ("cool", colorous::COOL),
+    ("cubehelix", colorous::CUBEHELIX),
+    ("blue_green", colorous::BLUE_GREEN),
+    ("blue_purple", colorous::BLUE_PURPLE),
+    ("green_blue", colorous::GREEN_BLUE),
+    ("orange_red", colorous::ORANGE_RED),
+    ("purple_blue_green", colorous::PURPLE_BLUE_GREEN),
+    ("purple_blue", colorous::PURPLE_BLUE),
+    ("purple_red", colorous::PURPLE_RED),
+    ("red_purple", colorous::RED_PURPLE),
+    ("yellow_green_blue", colorous::YELLOW_GREEN_BLUE),
+    ("yellow_green", colorous::YELLOW_GREEN),
+    ("yellow_orange_brown", colorous::YELLOW_ORANGE_BROWN),
+    ("yellow_orange_red", colorous::YELLOW_ORANGE_RED),
+    // sequential (single hue)
+    ("blues", colorous::BLUES),
+    ("greens", colorous::GREENS),
+    ("greys", colorous::GREYS),
+    ("oranges", colorous::ORANGES),
+    ("purples", colorous::PURPLES),
+    ("reds", colorous::REDS),
+    // diverging
+    ("brown_green", colorous::BROWN_GREEN),
+    ("purple_green", colorous::PURPLE_GREEN),
+    ("pink_green", colorous::PINK_GREEN),
+    ("purple_orange", colorous::PURPLE_ORANGE),
+    ("red_blue", colorous::RED_BLUE),
+    ("red_grey", colorous::RED_GREY),
+    ("red_yellow_blue", colorous::RED_YELLOW_BLUE),
+    ("red_yellow_green", colorous::RED_YELLOW_GREEN),
+    ("spectral", colorous::SPECTRAL),
+];
+
+// the colorous gradients that wrap around (first colour == last colour)
+static CYCLIC_GRADIENTS: &[(&str, Gradient)] = &[
+    ("rainbow", colorous::RAINBOW),
+    ("sinebow", colorous::SINEBOW),
+];
+
+// a stop of a user-defined gradient: position in [0, 1] and an rgb triple
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub pos: f32,
+    pub rgb: [f32; 3],
+}
+
+// maps a value in [0, 1] to an rgb triple (each channel in [0, 1])
+#[derive(Debug, Clone)]
+pub enum Colormap {
+    // one of the sequential or diverging colorous gradients
+    Gradient(Gradient),
+    // a colorous gradient that wraps around, suitable for phase
+    CyclicGradient(Gradient),
+    // the classic hsv colour wheel (R→G→B)
+    Hsv,
+    // perceptually uniform colour wheel: constant lightness and chroma in OkLab,
+    // so no phase looks "brighter" than another (unlike the hsv wheel)
+    Phase,
+    // user-defined gradient, linearly interpolated between stops
+    Stops(Vec<ColorStop>),
+}
+
+impl Colormap {
+    // accepts either a name ("inferno", "red_blue", "phase", ...) or a list
+    // of gradient stops like "#000000,#ff8800,#ffffff" (evenly spaced) or
+    // "0:#000000,0.2:#ff8800,1:#ffffff"
+    pub fn parse(spec: &str) -> Result<Colormap, String> {
+        if spec.contains('#') {
+            return Self::parse_stops(spec);
+        }
+        Self::from_name(spec)
+    }
+
+    pub fn from_name(name: &str) -> Result<Colormap, String> {
+        let name = name.trim().to_lowercase().replace('-', "_");
+        match &name[..] {
+            "hsv" => return Ok(Colormap::Hsv),
+            "phase" => return Ok(Colormap::Phase),
+            _ => (),
+        }
+        if let Some((_, g)) = GRADIENTS.iter().find(|(n, _)| *n == name) {
+            return Ok(Colormap::Gradient(*g));
+        }
+        if let Some((_, g)) = CYCLIC_GRADIENTS.iter().find(|(n, _)| *n == name) {
+            return Ok(Colormap::CyclicGradient(*g));
+        }
+        Err(format!("Unknown colormap: {}", name))
+    }
+
+    pub fn parse_stops(spec: &str) -> Result<Colormap, String> {
+        let parts: Vec<&str> = spec.split(',').map(|s| s.trim()).collect();
+        if parts.len() < 2 {
+            return Err("A gradient needs at least 2 stops".to_string());
+        }
+        let mut stops = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            let (pos, hex) = match part.split_once(':') {
+                Some((pos, hex)) => (
+                    pos.trim()
+                        .parse::<f32>()
+                        .map_err(|_| format!("Bad stop position: {}", pos))?,
+                    hex.trim(),
+                ),
+                None => (i as f32 / (parts.len() - 1) as f32, *part),
+            };
+            stops.push(ColorStop {
+                pos: pos.clamp(0., 1.),
+                rgb: parse_hex(hex)?,
+            });
+        }
+        Self::from_stops(stops)
+    }
+
+    // sorts the stops by position, an empty list is an error
+    pub fn from_stops(mut stops: Vec<ColorStop>) -> Result<Colormap, String> {
+        if stops.is_empty() {
+            return Err("A gradient needs at least 1 stop".to_string());
+        }
+        stops.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+        Ok(Colormap::Stops(stops))
+    }
+
+    // names accepted by from_name
+    pub fn names() -> Vec<&'static str> {
+        let mut names = vec!["hsv", "phase"];
+        names.extend(CYCLIC_GRADIENTS.iter().map(|(n, _)| *n));
+        names.extend(GRADIENTS.iter().map(|(n, _)| *n));
+        names
+    }
+
+    pub fn is_cyclic(&self) -> bool {
+        matches!(
+            self,
+            Colormap::CyclicGradient(_) | Colormap::Hsv | Colormap::Phase
+        )
+    }
+
+    // cyclic maps wrap t around, the others clamp it to [0, 1]
+    pub fn eval(&self, t: f32) -> [f32; 3] {
+        let t = if self.is_cyclic() {
+            t.rem_euclid(1.)
+        } else {
+            t.clamp(0., 1.)
+        };
+        match self {
+            Colormap::Gradient(g) | Colormap::CyclicGradient(g) => {
+                let color = g.eval_continuous(t as f64);
+                // the rgb has to be from 0-1. /256 rather than /255, as
+                // evaluate always had it, so its colours don't shift
+                [
+                    color.r as f32 / 256.,
+                    color.g as f32 / 256.,
+                    color.b as f32 / 256.,
+                ]
+            }
+            Colormap::Hsv => hsv_to_rgb(t * 360., 1., 1.),
+            Colormap::Phase => oklch_to_rgb(0.72, 0.11, t * 2. * PI),
+            Colormap::Stops(stops) => eval_stops(stops, t),
+        }
+    }
+}
+
+// what drives the alpha channel of each vertex when colours are emitted as rgba
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMap {
+    // every vertex gets the same alpha (the style's opacity)
+    Constant,
+    // fades out where |f| is huge: alpha = 1 / (1 + |f| / scale)
+    Fade(f32),
+    // fully transparent where |f| is below the threshold, opaque elsewhere
+    Threshold(f32),
+    // the hidden component (the one that also picks the colour) as alpha
+    Hidden,
+}
+
+impl AlphaMap {
+    // accepts "constant", "fade:<scale>", "threshold:<value>" or "hidden"
+    pub fn parse(spec: &str) -> Result<AlphaMap, String> {
+        let spec = spec.trim().to_lowercase();
+        let (name, arg) = match spec.split_once(':') {
+            Some((name, arg)) => (
+                name.trim(),
+                Some(
+                    arg.trim()
+                        .parse::<f32>()
+                        .map_err(|_| format!("Bad alpha parameter: {}", arg))?,
+                ),
+            ),
+            None => (&spec[..], None),
+        };
+        match (name, arg) {
+            ("constant", None) => Ok(AlphaMap::Constant),
+            ("fade", Some(scale)) if scale > 0. => Ok(AlphaMap::Fade(scale)),
+            ("fade", None) => Ok(AlphaMap::Fade(1.)),
+            ("threshold", Some(threshold)) => Ok(AlphaMap::Threshold(threshold)),
+            ("hidden", None) => Ok(AlphaMap::Hidden),
+            _ => Err(format!("Unknown alpha mapping: {}", spec)),
+        }
+    }
+
+    // f is the function value, t the hidden component already squashed into [0, 1]
+    pub fn eval(&self, f: Complex32, t: f32) -> f32 {
+        let alpha = match *self {
+            AlphaMap::Constant => 1.,
+            AlphaMap::Fade(scale) => 1. / (1. + f.norm() / scale),
+            AlphaMap::Threshold(threshold) => {
+                if f.norm() < threshold {
+                    0.
+                } else {
+                    1.
+                }
+            }
+            AlphaMap::Hidden => t,
+        };
+        // NaN where f is undefined, treat it as invisible
+        if alpha.is_nan() {
+            0.
+        } else {
+            alpha.clamp(0., 1.)
+        }
+    }
+}
+
+// a colormap together with the adjustments applied on top of it
+#[derive(Debug, Clone)]
+pub struct ColorStyle {
+    pub map: Colormap,
+    // multiplies the hsv saturation of every colour
+    pub saturation: f32,
+    // multiplies the hsv value (brightness) of every colour
+    pub value: f32,
+    // scales the alpha channel when colours are emitted as rgba
+    pub opacity: f32,
+    // colours are rgba when set, rgb otherwise
+    pub alpha: Option<AlphaMap>,
+    // the colour of points where f is undefined
+    pub undefined: [f32; 3],
+}
+
+// the default ColorStyle::undefined, a neutral mid grey
+pub const UNDEFINED_COLOR: [f32; 3] = [0.5, 0.5, 0.5];
+
+// the colormap and saturation evaluate uses for a graph type: inferno for
+// Re-Im/Im-Re, a softened hsv wheel for Mod-Arg and the Riemann sphere
+pub fn default_colormap(graph_type: u8) -> (&'static str, f32) {
+    match graph_type {
+        0..=3 => ("inferno", 1.),
+        _ => ("hsv", 0.75),
+    }
+}
+
+impl ColorStyle {
+    pub fn new(map: Colormap) -> ColorStyle {
+        ColorStyle {
+            map,
+            saturation: 1.,
+            value: 1.,
+            opacity: 1.,
+            alpha: None,
+            undefined: UNDEFINED_COLOR,
+        }
+    }
+
+    // see default_colormap
+    pub fn default_for(graph_type: u8) -> ColorStyle {
+        let (name, saturation) = default_colormap(graph_type);
+        let mut style = ColorStyle::new(Colormap::parse(name).unwrap());
+        style.saturation = saturation;
+        style
+    }
+
+    pub fn rgb(&self, t: f32) -> [f32; 3] {
+        let rgb = self.map.eval(t);
+        if self.saturation == 1. && self.value == 1. {
+            return rgb;
+        }
+        let (h, s, v) = rgb_to_hsv(rgb);
+        hsv_to_rgb(
+            h,
+            (s * self.saturation).clamp(0., 1.),
+            (v * self.value).clamp(0., 1.),
+        )
+    }
+
+    // the colormap as in Colormap::parse, an empty `alpha` keeps the colours
+    // rgb, otherwise it is parsed with AlphaMap::parse
+    pub fn parse(
+        colormap: &str,
+        saturation: f32,
+        value: f32,
+        opacity: f32,
+        alpha: &str,
+    ) -> Result<ColorStyle, String> {
+        let mut style = ColorStyle::new(Colormap::parse(colormap)?);
+        style.saturation = saturation;
+        style.value = value;
+        style.opacity = opacity;
+        if !alpha.is_empty() {
+            style.alpha = Some(AlphaMap::parse(alpha)?);
+        }
+        Ok(style)
+    }
+
+    // number of floats per vertex in the colour array
+    pub fn channels(&self) -> usize {
+        match self.alpha {
+            Some(_) => 4,
+            None => 3,
+        }
+    }
+
+    pub fn alpha(&self, f: Complex32, t: f32) -> Option<f32> {
+        self.alpha.map(|alpha| alpha.eval(f, t) * self.opacity)
+    }
+}
+
+// parses "#rrggbb" (or "rrggbb") into an rgb triple
+fn parse_hex(hex: &str) -> Result<[f32; 3], String> {
+    let digits = hex.trim_start_matches('#');
+    if digits.len() != 6 || !digits.is_ascii() {
+        return Err(format!("Bad colour: {}", hex));
+    }
+    let mut rgb = [0.; 3];
+    for (i, channel) in rgb.iter_mut().enumerate() {
+        let byte = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16)
+            .map_err(|_| format!("Bad colour: {}", hex))?;
+        *channel = byte as f32 / 255.;
+    }
+    Ok(rgb)
+}
+
+// Colormap::Stops built by hand can be empty, that's undefined grey
+fn eval_stops(stops: &[ColorStop], t: f32) -> [f32; 3] {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return UNDEFINED_COLOR,
+    };
+    if t <= first.pos {
+        return first.rgb;
+    }
+    if t >= last.pos {
+        return last.rgb;
+    }
+    for pair in stops.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if t <= b.pos {
+            let span = b.pos - a.pos;
+            let f = if span > 0. { (t - a.pos) / span } else { 1. };
+            return [
+                a.rgb[0] + (b.rgb[0] - a.rgb[0]) * f,
+                a.rgb[1] + (b.rgb[1] - a.rgb[1]) * f,
+                a.rgb[2] + (b.rgb[2] - a.rgb[2]) * f,
+            ];
+        }
+    }
+    last.rgb
+}
+
+// accepts h in degrees, s and v in [0, 1]
+pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [f32; 3] {
+    let h = h.rem_euclid(360.);
+    let c = s * v;
+    let i = h / 60.;
+    let x = c * (1. - (i % 2. - 1.).abs());
+
+    let (r1, g1, b1) = match i.floor() as i8 {
+        0 => (c, x, 0.),
+        1 => (x, c, 0.),
+        2 => (0., c, x),
+        3 => (0., x, c),
+        4 => (x, 0., c),
+        5 => (c, 0., x),
+        _ => (0., 0., 0.),
+    };
+
+    let m = v - c;
+    [r1 + m, g1 + m, b1 + m]
+}
+
+// returns h in degrees, s and v in [0, 1]
+pub fn rgb_to_hsv(rgb: [f32; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let h = if delta == 0. {
+        0.
+    } else if max == r {
+        60. * ((g - b) / delta).rem_euclid(6.)
+    } else if max == g {
+        60. * ((b - r) / delta + 2.)
+    } else {
+        60. * ((r - g) / delta + 4.)
+    };
+    let s = if max == 0. { 0. } else { delta / max };
+    (h, s, max)
+}
+
+// OkLab in polar form (lightness, chroma, hue in radians) to gamma-encoded sRGB
+fn oklch_to_rgb(l: f32, c: f32, h: f32) -> [f32; 3] {
+    let (a, b) = (c * h.cos(), c * h.sin());
+
+    let l_ = l + 0.39633778 * a + 0.21580376 * b;
+    let m_ = l - 0.105561346 * a - 0.06385417 * b;
+    let s_ = l - 0.08948418 * a - 1.2914855 * b;
+    let (l3, m3, s3) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+    let r = 4.0767417 * l3 - 3.3077116 * m3 + 0.23096993 * s3;
+    let g = -1.268438 * l3 + 2.6097574 * m3 - 0.3413194 * s3;
+    let b = -0.0041960863 * l3 - 0.7034186 * m3 + 1.7076147 * s3;
+    [srgb_gamma(r), srgb_gamma(g), srgb_gamma(b)]
+}
+
+// linear light to gamma-encoded sRGB, clamped into gamut
+fn srgb_gamma(x: f32) -> f32 {
+    let x = x.clamp(0., 1.);
+    if x <= 0.0031308 {
+        12.92 * x
+    } else {
+        1.055 * x.powf(1. / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops() {
+        assert!(Colormap::from_stops(Vec::new()).is_err());
+        assert_eq!(Colormap::Stops(Vec::new()).eval(0.5), UNDEFINED_COLOR);
+        let map = Colormap::parse("#000000,0.75:#ffffff,0.25:#ff0000").unwrap();
+        assert_eq!(map.eval(0.), [0., 0., 0.]);
+        assert_eq!(map.eval(0.25), [1., 0., 0.]);
+        assert_eq!(map.eval(0.5), [1., 0.5, 0.5]);
+        assert_eq!(map.eval(2.), [1., 1., 1.]);
+        let single = Colormap::from_stops(vec![ColorStop {
+            pos: 0.5,
+            rgb: [0.2, 0.4, 0.6],
+        }])
+        .unwrap();
+        assert_eq!(single.eval(0.9), [0.2, 0.4, 0.6]);
+    }
+
+    #[test]
+    fn gradients_divide_by_256() {
+        // inferno ends at #fcffa4
+        let rgb = Colormap::from_name("inferno").unwrap().eval(1.);
+        assert_eq!(rgb, [252. / 256., 255. / 256., 164. / 256.]);
+    }
+
+    #[test]
+    fn defaults() {
+        for graph_type in 0..=6 {
+            let style = ColorStyle::default_for(graph_type);
+            let (name, saturation) = default_colormap(graph_type);
+            assert_eq!(style.saturation, saturation);
+            assert_eq!(style.rgb(0.3), {
+                let mut named = ColorStyle::new(Colormap::from_name(name).unwrap());
+                named.saturation = saturation;
+                named.rgb(0.3)
+            });
+        }
+        assert_eq!(default_colormap(0), ("inferno", 1.));
+        assert_eq!(default_colormap(4), ("hsv", 0.75));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::parser::{RPNExpr, ShuntingParser};
+use crate::polyline::Polylines;
+use crate::rpneval::MathContext;
+use crate::vector_field::MIN_SPACING;
+use num_complex::Complex32;
+use std::f32::consts::PI;
+use wasm_bindgen::prelude::*;
+
+// half the side of the plotted square of the input plane
+const EXTENT: f32 = 10.;
+// evenly spaced samples along each curve before any refinement
+const INITIAL_SAMPLES: usize = 64;
+// each initial interval is halved at most this many times
+const MAX_DEPTH: u32 = 10;
+// the most rays in a family, as many as lines with the finest spacing
+const MAX_RAYS: f32 = 2. * EXTENT / MIN_SPACING;
+
+// a curve in the input plane, parametrized over t in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    // the straight line from the first point to the second
+    Segment(Complex32, Complex32),
+    // centre and radius, counter-clockwise from the rightmost point
+    Circle(Complex32, f32),
+}
+
+impl Curve {
+    pub fn point(&self, t: f32) -> Complex32 {
+        match *self {
+            Curve::Segment(from, to) => from + (to - from) * t,
+            Curve::Circle(centre, radius) => centre + Complex32::from_polar(radius, 2. * PI * t),
+        }
+    }
+
+    // a ';' separated list of families over [-10, 10] x [-10, 10]:
+    // "grid[:spacing]", "horizontal[:spacing]", "vertical[:spacing]",
+    // "circles[:spacing]" (around 0), "rays[:count]" (from 0),
+    // "polar[:spacing[:count]]" (circles and rays), or single curves
+    // "line:x0:y0:x1:y1" and "circle:x:y:radius". spacings have to be at
+    // least MIN_SPACING, like the vector field's, and counts at most MAX_RAYS
+    pub fn parse(spec: &str) -> Result<Vec<Curve>, String> {
+        let spacing = |s: f32| {
+            if s >= MIN_SPACING {
+                Ok(s)
+            } else {
+                Err(format!(
+                    "Bad curve spacing: {}, it has to be at least {}",
+                    s, MIN_SPACING
+                ))
+            }
+        };
+        let count = |c: f32| {
+            if (1. ..=MAX_RAYS).contains(&c) {
+                Ok(c as usize)
+            } else {
+                Err(format!(
+                    "Bad ray count: {}, it has to be from 1 to {}",
+                    c, MAX_RAYS
+                ))
+            }
+        };
+        let mut curves = Vec::new();
+        for family in spec.split(';').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let family = family.to_lowercase();
+            let mut parts = family.split(':').map(|s| s.trim());
+            let name = parts.next().unwrap_or("");
+            let mut args = Vec::new();
+            for arg in parts {
+                args.push(
+                    arg.parse::<f32>()
+                        .map_err(|_| format!("Bad curve parameter: {}", arg))?,
+                );
+            }
+            match (name, &args[..]) {
+                ("grid", []) => {
+                    curves.extend(lines(1., false));
+                    curves.extend(lines(1., true));
+                }
+                ("grid", [s]) => {
+                    let s = spacing(*s)?;
+                    curves.extend(lines(s, false));
+                    curves.extend(lines(s, true));
+                }
+                ("horizontal", []) => curves.extend(lines(1., false)),
+                ("horizontal", [s]) => curves.extend(lines(spacing(*s)?, false)),
+                ("vertical", []) => curves.extend(lines(1., true)),
+                ("vertical", [s]) => curves.extend(lines(spacing(*s)?, true)),
+                ("circles", []) => curves.extend(circles(1.)),
+                ("circles", [s]) => curves.extend(circles(spacing(*s)?)),
+                ("rays", []) => curves.extend(rays(12)),
+                ("rays", [c]) => curves.extend(rays(count(*c)?)),
+                ("polar", []) => {
+                    curves.extend(circles(1.));
+                    curves.extend(rays(12));
+                }
+                ("polar", [s]) => {
+                    curves.extend(circles(spacing(*s)?));
+                    curves.extend(rays(12));
+                }
+                ("polar", [s, c]) => {
+                    curves.extend(circles(spacing(*s)?));
+                    curves.extend(rays(count(*c)?));
+                }
+                ("line", [x0, y0, x1, y1]) => curves.push(Curve::Segment(
+                    Complex32::new(*x0, *y0),
+                    Complex32::new(*x1, *y1),
+                )),
+                ("circle", [x, y, radius]) if *radius > 0. => {
+                    curves.push(Curve::Circle(Complex32::new(*x, *y), *radius))
+                }
+                _ => return Err(format!("Unknown curve family: {}", family)),
+            }
+        }
+        Ok(curves)
+    }
+}
+
+// lines across the square every `spacing`, through 0
+fn lines(spacing: f32, vertical: bool) -> Vec<Curve> {
+    let k = (EXTENT / spacing).floor() as i32;
+    (-k..=k)
+        .map(|k| {
+            let c = k as f32 * spacing;
+            if vertical {
+                Curve::Segment(Complex32::new(c, -EXTENT), Complex32::new(c, EXTENT))
+            } else {
+                Curve::Segment(Complex32::new(-EXTENT, c), Complex32::new(EXTENT, c))
+            }
+        })
+        .collect()
+}
+
+// circles around 0 every `spacing` up to radius 10
+fn circles(spacing: f32) -> Vec<Curve> {
+    let k = (EXTENT / spacing).floor() as i32;
+    (1..=k)
+        .map(|k| Curve::Circle(Complex32::new(0., 0.), k as f32 * spacing))
+        .collect()
+}
+
+// `count` rays from 0 out to radius 10, the first along the positive real axis
+fn rays(count: usize) -> Vec<Curve> {
+    (0..count)
+        .map(|k| {
+            let angle = 2. * PI * k as f32 / count as f32;
+            Curve::Segment(Complex32::new(0., 0.), Complex32::from_polar(EXTENT, angle))
+        })
+        .collect()
+}
+
+// the images of the curves under f, as polylines (re f, im f, 0). each curve
+// is sampled adaptively: an interval is halved until the image of its
+// midpoint is within `tolerance` of the midpoint of the image chord. lines
+// always break where f is undefined or infinite, and with `split` also where
+// the image still jumps by more than 100 tolerances at the finest level and
+// the jump doesn't shrink when halving: a pole or a branch cut rather than a
+// steep part
+pub fn map_curves(
+    expr: &RPNExpr,
+    curves: &[Curve],
+    tolerance: f32,
+    split: bool,
+) -> Result<Polylines, String> {
+    if tolerance.is_nan() || tolerance <= 0. {
+        return Err(format!("Bad tolerance: {}", tolerance));
+    }
+    let ctx = MathContext::new();
+    let mut polylines = Polylines::new();
+    for curve in curves {
+        let mut tracer = Tracer {
+            ctx: &ctx,
+            expr,
+            curve,
+            tolerance,
+            split,
+            lines: &mut polylines,
+            current: Vec::new(),
+        };
+        tracer.trace()?;
+    }
+    Ok(polylines)
+}
+
+#[wasm_bindgen]
+pub fn conformal_grid(
+    input: &str,
+    curves: &str,
+    tolerance: f32,
+    split: bool,
+) -> Result<Polylines, JsValue> {
+    let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+    let curves = Curve::parse(curves).map_err(|e| JsValue::from_str(&e))?;
+    map_curves(&expr, &curves, tolerance, split).map_err(|e| JsValue::from_str(&e))
+}
+
+struct Tracer<'a> {
+    ctx: &'a MathContext,
+    expr: &'a RPNExpr,
+    curve: &'a Curve,
+    tolerance: f32,
+    split: bool,
+    lines: &'a mut Polylines,
+    // the piece of the image being built
+    current: Vec<[f32; 3]>,
+}
+
+impl Tracer<'_> {
+    fn trace(&mut self) -> Result<(), String> {
+        let mut t0 = 0.;
+        let mut w0 = self.image(t0)?;
+        self.add(w0);
+        for k in 1..=INITIAL_SAMPLES {
+            let t1 = k as f32 / INITIAL_SAMPLES as f32;
+            let w1 = self.image(t1)?;
+            self.refine(t0, w0, t1, w1, 0)?;
+            t0 = t1;
+            w0 = w1;
+        }
+        self.finish_line();
+        Ok(())
+    }
+
+    fn image(&self, t: f32) -> Result<Complex32, String> {
+        self.ctx.eval(self.expr, self.curve.point(t))
+    }
+
+    // adds the points of the image after t0 up to and including t1
+    fn refine(
+        &mut self,
+        t0: f32,
+        w0: Complex32,
+        t1: f32,
+        w1: Complex32,
+        depth: u32,
+    ) -> Result<(), String> {
+        // nothing to draw in between, no need to look closer
+        if !w0.is_finite() && !w1.is_finite() {
+            self.add(w1);
+            return Ok(());
+        }
+        let tm = (t0 + t1) / 2.;
+        let wm = self.image(tm)?;
+        let deviation = (wm - (w0 + w1) / 2.).norm();
+        let smooth = w0.is_finite() && w1.is_finite() && deviation <= self.tolerance;
+        if smooth {
+            self.add(w1);
+        } else if depth == MAX_DEPTH {
+            // a steep but continuous stretch about halves its jump with the
+            // interval, across a discontinuity one half keeps nearly all of it
+            let jump = (w1 - w0).norm();
+            let halves = (wm - w0).norm().max((w1 - wm).norm());
+            if self.split
+                && jump > 100. * self.tolerance
+                && (halves.is_nan() || halves >= 0.75 * jump)
+            {
+                self.finish_line();
+            }
+            self.add(w1);
+        } else {
+            self.refine(t0, w0, tm, wm, depth + 1)?;
+            self.refine(tm, wm, t1, w1, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    // a non-finite point ends the current piece
+    fn add(&mut self, w: Complex32) {
+        if w.is_finite() {
+            self.current.push([w.re, w.im, 0.]);
+        } else {
+            self.finish_line();
+        }
+    }
+
+    fn finish_line(&mut self) {
+        self.lines.push(&self.current);
+        self.current.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spacing_limits() {
+        for spec in [
+            "grid:0.01",
+            "horizontal:0",
+            "vertical:-1",
+            "circles:1e-9",
+            "polar:0.001",
+            "polar:1:1e9",
+            "rays:0",
+            "rays:100000",
+            "grid:nan",
+        ] {
+            assert!(Curve::parse(spec).is_err(), "{}", spec);
+        }
+        assert_eq!(Curve::parse("grid:0.05").unwrap().len(), 2 * 401);
+        assert_eq!(Curve::parse("circles:0.05").unwrap().len(), 200);
+        assert_eq!(Curve::parse("rays:400").unwrap().len(), 400);
+        assert_eq!(Curve::parse("polar:2:6").unwrap().len(), 5 + 6);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::polyline::Polylines;
+use num_complex::Complex32;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+// the scalar field a contour is a level set of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantity {
+    Re,
+    Im,
+    Abs,
+    // the argument, in (-π, π], which is compared modulo 2π
+    Arg,
+}
+
+impl Quantity {
+    // accepts "re", "im", "abs" (or "mod") and "arg"
+    pub fn parse(spec: &str) -> Result<Quantity, String> {
+        match &spec.trim().to_lowercase()[..] {
+            "re" => Ok(Quantity::Re),
+            "im" => Ok(Quantity::Im),
+            "abs" | "mod" => Ok(Quantity::Abs),
+            "arg" => Ok(Quantity::Arg),
+            spec => Err(format!("Unknown contour quantity: {}", spec)),
+        }
+    }
+
+    pub fn of(&self, f: Complex32) -> f32 {
+        match *self {
+            Quantity::Re => f.re,
+            Quantity::Im => f.im,
+            Quantity::Abs => f.norm(),
+            Quantity::Arg => f.arg(),
+        }
+    }
+}
+
+// marching squares over a real-major cols x rows grid (value k belongs to
+// vertex (k / rows, k % rows)), with each vertex at points[3k..3k + 3]. the
+// crossings are interpolated linearly along the cell edges, in all three
+// coordinates, and chained into polylines
+//
+// with `cyclic` the values are angles and only their difference to the level
+// modulo 2π counts: an edge where that difference jumps by more than π is
+// crossing the cut opposite the level, not the level. cells around a zero or
+// pole then have an odd number of crossings, the line ends in their centre
+pub fn level_set(
+    values: &[f32],
+    cols: usize,
+    rows: usize,
+    points: &[f32],
+    level: f32,
+    cyclic: bool,
+) -> Polylines {
+    let diff = |k: usize| {
+        let d = values[k] - level;
+        if cyclic {
+            // into [-π, π)
+            (d + PI).rem_euclid(2. * PI) - PI
+        } else {
+            d
+        }
+    };
+    let point = |k: usize| [points[3 * k], points[3 * k + 1], points[3 * k + 2]];
+    // where the edge from vertex p to q crosses the level, if it does
+    let crossing = |p: usize, q: usize| -> Option<[f32; 3]> {
+        let (dp, dq) = (diff(p), diff(q));
+        if !dp.is_finite() || !dq.is_finite() || (dp < 0.) == (dq < 0.) {
+            return None;
+        }
+        if cyclic && (dp - dq).abs() > PI {
+            return None;
+        }
+        let t = dp / (dp - dq);
+        let (a, b) = (point(p), point(q));
+        Some([
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+        ])
+    };
+
+    // every segment joins 2 keys: edges are 2 * vertex (+ 1 along j) of
+    // their lower end, cell centres come after all edges
+    let centre_key = |i: usize, j: usize| 2 * cols * rows + i * rows + j;
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+    let mut at: HashMap<usize, [f32; 3]> = HashMap::new();
+    for i in 0..cols.saturating_sub(1) {
+        for j in 0..rows.saturating_sub(1) {
+            let a = i * rows + j;
+            let b = (i + 1) * rows + j;
+            let c = b + 1;
+            let d = a + 1;
+            // counter-clockwise: bottom, right, top, left
+            let edges = [
+                (a, b, 2 * a),
+                (b, c, 2 * b + 1),
+                (d, c, 2 * d),
+                (a, d, 2 * a + 1),
+            ];
+            let mut hits = Vec::with_capacity(4);
+            for &(p, q, key) in edges.iter() {
+                if let Some(x) = crossing(p, q) {
+                    at.insert(key, x);
+                    hits.push(key);
+                }
+            }
+            match hits.len() {
+                2 => segments.push((hits[0], hits[1])),
+                4 => {
+                    // a saddle: the centre decides which corners are joined
+                    let centre = (diff(a) + diff(b) + diff(c) + diff(d)) / 4.;
+                    if (centre < 0.) == (diff(a) < 0.) {
+                        // a and c are connected, cut off b and d
+                        segments.push((hits[0], hits[1]));
+                        segments.push((hits[2], hits[3]));
+                    } else {
+                        segments.push((hits[3], hits[0]));
+                        segments.push((hits[1], hits[2]));
+                    }
+                }
+                1 | 3 => {
+                    let key = centre_key(i, j);
+                    let corners = [point(a), point(b), point(c), point(d)];
+                    let mut mid = [0.; 3];
+                    for corner in corners.iter() {
+                        for (m, x) in mid.iter_mut().zip(corner) {
+                            *m += x / 4.;
+                        }
+                    }
+                    at.insert(key, mid);
+                    for &hit in hits.iter() {
+                        segments.push((hit, key));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    chain(&segments, &at)
+}
+
+// joins segments that share an end into polylines
+fn chain(segments: &[(usize, usize)], at: &HashMap<usize, [f32; 3]>) -> Polylines {
+    let mut by_key: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (s, &(p, q)) in segments.iter().enumerate() {
+        by_key.entry(p).or_default().push(s);
+        by_key.entry(q).or_default().push(s);
+    }
+    let mut used = vec![false; segments.len()];
+    // the segment after `s` continuing from `key`
+    let next = |key: usize, s: usize, used: &[bool]| {
+        by_key[&key].iter().copied().find(|&t| t != s && !used[t])
+    };
+    let mut lines = Polylines::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let (p, q) = segments[start];
+        // forward from q, then backward from p
+        let mut forward = vec![p, q];
+        let (mut key, mut s) = (q, start);
+        while let Some(t) = next(key, s, &used) {
+            used[t] = true;
+            key = if segments[t].0 == key {
+                segments[t].1
+            } else {
+                segments[t].0
+            };
+            forward.push(key);
+            s = t;
+        }
+        let mut backward = Vec::new();
+        let (mut key, mut s) = (p, start);
+        while let Some(t) = next(key, s, &used) {
+            used[t] = true;
+            key = if segments[t].0 == key {
+                segments[t].1
+            } else {
+                segments[t].0
+            };
+            backward.push(key);
+            s = t;
+        }
+        backward.reverse();
+        backward.extend(forward);
+        let line: Vec<[f32; 3]> = backward.iter().map(|key| at[key]).collect();
+        lines.push(&line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // f on the n x n grid with coordinates lo, lo + h, ..., as level_set's
+    // (values, points)
+    fn grid(n: usize, lo: f32, h: f32, f: impl Fn(f32, f32) -> f32) -> (Vec<f32>, Vec<f32>) {
+        let (mut values, mut points) = (Vec::new(), Vec::new());
+        for i in 0..n {
+            for j in 0..n {
+                let (x, y) = (lo + i as f32 * h, lo + j as f32 * h);
+                values.push(f(x, y));
+                points.extend_from_slice(&[x, y, 0.]);
+            }
+        }
+        (values, points)
+    }
+
+    fn closed(line: &[[f32; 3]]) -> bool {
+        line.len() > 2 && line[0] == line[line.len() - 1]
+    }
+
+    #[test]
+    fn circle() {
+        let (values, points) = grid(21, -2., 0.2, f32::hypot);
+        let lines = level_set(&values, 21, 21, &points, 1., false);
+        assert_eq!(lines.len(), 1);
+        let line = lines.line(0);
+        assert!(closed(&line));
+        assert!(line.len() > 30);
+        for p in line.iter() {
+            assert!((p[0].hypot(p[1]) - 1.).abs() < 0.02, "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn saddle() {
+        // x y is linear along the edges, so the crossings are exact. the
+        // centre cell has all 4 edges crossed, its mean decides which
+        // quadrants the 2 branches of x y = level go through
+        let (values, points) = grid(8, -1.75, 0.5, |x, y| x * y);
+        for level in [0.05, -0.05] {
+            let lines = level_set(&values, 8, 8, &points, level, false);
+            assert_eq!(lines.len(), 2);
+            for line in lines.lines() {
+                assert!(!closed(&line));
+                let side = line[0][0] > 0.;
+                for p in line.iter() {
+                    assert!((p[0] * p[1] - level).abs() < 1e-6, "{:?}", p);
+                    assert_eq!(p[0] > 0., side);
+                    assert_eq!(p[1] > 0., (level > 0.) == side);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arg_around_a_zero() {
+        // the zero of z in the middle of the centre cell: arg z = 0.5 is a
+        // ray from that centre, the jump across the negative real axis and
+        // the opposite ray at 0.5 - π aren't crossings
+        let (values, points) = grid(8, -1.75, 0.5, |x, y| y.atan2(x));
+        let lines = level_set(&values, 8, 8, &points, 0.5, true);
+        assert_eq!(lines.len(), 1);
+        let line = lines.line(0);
+        assert!(!closed(&line));
+        let centre = [0., 0., 0.];
+        assert!(line[0] == centre || line[line.len() - 1] == centre);
+        for p in line.iter().filter(|&&p| p != centre) {
+            assert!((p[1].atan2(p[0]) - 0.5).abs() < 0.1, "{:?}", p);
+        }
+        // the level modulo 2π is the same line
+        let wrapped = level_set(&values, 8, 8, &points, 0.5 - 2. * PI, true);
+        assert_eq!(wrapped.points().len(), lines.points().len());
+        for (a, b) in wrapped.points().iter().zip(lines.points()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+        // compared as plain numbers the line goes on along the cut
+        let plain = level_set(&values, 8, 8, &points, 0.5, false);
+        assert!(plain.points().chunks(3).any(|p| p[0] < -1.));
+    }
+
+    #[test]
+    fn odd_crossings_meet_in_the_centre() {
+        // 1 cell, counter-clockwise a b c d, with the edge from d to c
+        // jumping across the cut: 3 crossings, joined in the centre, which
+        // chain makes into one line through it and one ending there
+        let values = [-0.5, 2.5, 1., -1.];
+        let points = [0., 0., 0., 0., 1., 0., 1., 0., 0., 1., 1., 0.];
+        let lines = level_set(&values, 2, 2, &points, 0., true);
+        assert_eq!(lines.len(), 2);
+        let centre = [0.5, 0.5, 0.];
+        let (long, short) = (lines.line(0), lines.line(1));
+        let (long, short) = if long.len() > short.len() {
+            (long, short)
+        } else {
+            (short, long)
+        };
+        assert_eq!(long.len(), 3);
+        assert_eq!(long[1], centre);
+        assert_eq!(short.len(), 2);
+        assert!(short.contains(&centre));
+    }
+
+    #[test]
+    fn chains_segments() {
+        // a square given out of order and in both directions, and an open
+        // path of 2 segments
+        let at: HashMap<usize, [f32; 3]> = (0..8).map(|k| (k, [k as f32, 0., 0.])).collect();
+        let segments = [(1, 2), (5, 6), (3, 0), (2, 3), (7, 6), (0, 1)];
+        let lines = chain(&segments, &at);
+        assert_eq!(lines.len(), 2);
+        let square = lines.line(0);
+        assert_eq!(square.len(), 5);
+        assert!(closed(&square));
+        let path: Vec<f32> = lines.line(1).iter().map(|p| p[0]).collect();
+        assert!(path == [5., 6., 7.] || path == [7., 6., 5.], "{:?}", path);
+    }
+}
@@ -0,0 +1,64 @@
+use num_complex::Complex32;
+use std::f32::consts::PI;
+
+// which inputs the [-10, 10] x [-10, 10] grid stands for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Domain {
+    // the grid point itself
+    Plane,
+    // the grid is a chart of the Riemann sphere seen from below: the distance
+    // from the origin is the angle from the south pole (0), scaled so the
+    // circle of radius 10 is the north pole (∞). the disc covers the whole
+    // extended plane, the corners go past ∞ and come back around
+    Sphere,
+}
+
+impl Domain {
+    // accepts "plane" (or "") and "sphere"
+    pub fn parse(spec: &str) -> Result<Domain, String> {
+        match &spec.trim().to_lowercase()[..] {
+            "plane" | "" => Ok(Domain::Plane),
+            "sphere" => Ok(Domain::Sphere),
+            spec => Err(format!("Unknown domain: {}", spec)),
+        }
+    }
+
+    // the input z for a grid point
+    pub fn z(&self, point: Complex32) -> Complex32 {
+        match *self {
+            Domain::Plane => point,
+            Domain::Sphere => {
+                let r = point.norm();
+                if r == 0. {
+                    return point;
+                }
+                // stereographic projection of the point at angle theta from
+                // the south pole has modulus tan(theta / 2)
+                let theta = PI * r / 10.;
+                point / r * (theta / 2.).tan()
+            }
+        }
+    }
+}
+
+// vertical coordinate of w on the unit Riemann sphere, -1 at 0 and 1 at ∞
+pub fn sphere_height(w: Complex32) -> f32 {
+    let r = w.norm();
+    if r > 1. {
+        // divided through by r^2 so huge moduli (and ∞) don't overflow
+        let q = 1. / (r * r);
+        (1. - q) / (1. + q)
+    } else {
+        (r * r - 1.) / (r * r + 1.)
+    }
+}
+
+// chordal distance from w to 0 on the unit Riemann sphere, 2 at ∞
+pub fn chordal_distance(w: Complex32) -> f32 {
+    let r = w.norm();
+    if r > 1. {
+        2. / (1. / (r * r) + 1.).sqrt()
+    } else {
+        2. * r / (1. + r * r).sqrt()
+    }
+}
@@ -0,0 +1,268 @@
+use crate::domain::Domain;
+use crate::parser::{RPNExpr, ShuntingParser};
+use crate::plot::{grid_side, grid_z, Plot};
+use crate::rpneval::MathContext;
+use num_complex::Complex32;
+use wasm_bindgen::prelude::*;
+
+// evaluates the grid a few points at a time so the browser stays responsive
+// on slow functions (gamma, zeta at high density). with coarse_to_fine every
+// 8th point is done first, then every 4th, 2nd and finally the rest, so
+// preview() shows a rough surface almost immediately
+#[wasm_bindgen]
+pub struct Evaluator {
+    expr: RPNExpr,
+    ctx: MathContext,
+    n: i16,
+    // what the grid points stand for, as in Plot
+    domain: Domain,
+    side: usize,
+    samples: Vec<Complex32>,
+    evaluated: Vec<bool>,
+    // the strides of the passes, coarsest first, always ending with 1
+    strides: Vec<usize>,
+    // index into strides of the pass in progress
+    pass: usize,
+    // next sample (real-major index) to look at in the current pass
+    cursor: usize,
+    // number of samples evaluated so far
+    count: usize,
+    cancelled: bool,
+}
+
+impl Evaluator {
+    pub fn from_expr(
+        expr: RPNExpr,
+        n: i16,
+        domain: Domain,
+        coarse_to_fine: bool,
+    ) -> Result<Evaluator, String> {
+        let side = grid_side(n)?;
+        let strides = if coarse_to_fine {
+            vec![8, 4, 2, 1]
+        } else {
+            vec![1]
+        };
+        Ok(Evaluator {
+            expr,
+            ctx: MathContext::new(),
+            n,
+            domain,
+            side,
+            samples: vec![Complex32::new(f32::NAN, f32::NAN); side * side],
+            evaluated: vec![false; side * side],
+            strides,
+            pass: 0,
+            cursor: 0,
+            count: 0,
+            cancelled: false,
+        })
+    }
+
+    // evaluates at most max_points more samples, returns whether the whole
+    // grid is done
+    pub fn advance(&mut self, max_points: usize) -> Result<bool, String> {
+        let total = self.side * self.side;
+        let mut budget = max_points;
+        while !self.cancelled && self.pass < self.strides.len() && budget > 0 {
+            let stride = self.strides[self.pass];
+            if self.cursor >= total {
+                self.pass += 1;
+                self.cursor = 0;
+                continue;
+            }
+            let k = self.cursor;
+            self.cursor += 1;
+            let (i, j) = (k / self.side, k % self.side);
+            if i % stride != 0 || j % stride != 0 || self.evaluated[k] {
+                continue;
+            }
+            self.samples[k] = self
+                .ctx
+                .eval(&self.expr, self.domain.z(grid_z(self.n, i, j)))?;
+            self.evaluated[k] = true;
+            self.count += 1;
+            budget -= 1;
+        }
+        if self.cursor >= total && self.pass < self.strides.len() {
+            self.pass += 1;
+            self.cursor = 0;
+        }
+        Ok(self.is_done())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.count == self.side * self.side
+    }
+
+    // the grid as far as it is known: a sample that hasn't been evaluated yet
+    // takes the value of the closest evaluated sample on the last finished
+    // coarse pass (NaN if there is none yet)
+    pub fn preview_plot(&self) -> Plot {
+        let stride = match self.pass {
+            0 => return Plot::from_samples(self.n, self.domain, self.samples.clone()),
+            p => self.strides[(p - 1).min(self.strides.len() - 1)],
+        };
+        let mut samples = self.samples.clone();
+        for i in 0..self.side {
+            for j in 0..self.side {
+                let k = i * self.side + j;
+                if !self.evaluated[k] {
+                    let (ci, cj) = (nearest(i, stride, self.side), nearest(j, stride, self.side));
+                    samples[k] = self.samples[ci * self.side + cj];
+                }
+            }
+        }
+        Plot::from_samples(self.n, self.domain, samples)
+    }
+
+    pub fn into_plot(self) -> Result<Plot, String> {
+        if !self.is_done() {
+            return Err("Evaluation isn't finished".to_string());
+        }
+        Ok(Plot::from_samples(self.n, self.domain, self.samples))
+    }
+}
+
+#[wasm_bindgen]
+impl Evaluator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(input: &str, n: i16, coarse_to_fine: bool) -> Result<Evaluator, JsValue> {
+        let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+        Evaluator::from_expr(expr, n, Domain::Plane, coarse_to_fine)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    // like the constructor, over the given domain ("plane" or "sphere", see
+    // Plot.onDomain)
+    #[wasm_bindgen(js_name = onDomain)]
+    pub fn on_domain(
+        input: &str,
+        n: i16,
+        domain: &str,
+        coarse_to_fine: bool,
+    ) -> Result<Evaluator, JsValue> {
+        let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+        let domain = Domain::parse(domain).map_err(|e| JsValue::from_str(&e))?;
+        Evaluator::from_expr(expr, n, domain, coarse_to_fine).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // call repeatedly (e.g. once per animation frame) until it returns true
+    pub fn step(&mut self, max_points: usize) -> Result<bool, JsValue> {
+        self.advance(max_points).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // fraction of the grid evaluated so far, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        self.count as f32 / (self.side * self.side) as f32
+    }
+
+    // stops evaluation, further calls to step do nothing
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        self.is_done()
+    }
+
+    // the stride of the pass in progress (1 for the final pass)
+    #[wasm_bindgen(getter)]
+    pub fn stride(&self) -> usize {
+        self.strides[self.pass.min(self.strides.len() - 1)]
+    }
+
+    pub fn preview(&self) -> Plot {
+        self.preview_plot()
+    }
+
+    // consumes the evaluator, only once done
+    pub fn finish(self) -> Result<Plot, JsValue> {
+        self.into_plot().map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+// the multiple of stride closest to i, kept on the grid
+fn nearest(i: usize, stride: usize, side: usize) -> usize {
+    let down = i / stride * stride;
+    let up = down + stride;
+    if i - down <= up - i || up >= side {
+        down
+    } else {
+        up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(w: &[Complex32]) -> Vec<(u32, u32)> {
+        w.iter().map(|z| (z.re.to_bits(), z.im.to_bits())).collect()
+    }
+
+    #[test]
+    fn matches_sample() {
+        for input in ["z^2 + 1/z", "gamma(z) * sin(z)", "zeta(z)"] {
+            let expr = ShuntingParser::parse_str(input).unwrap();
+            for domain in [Domain::Plane, Domain::Sphere] {
+                let plot = Plot::sample_on(&expr, 2, domain).unwrap();
+                for coarse_to_fine in [false, true] {
+                    let mut ev =
+                        Evaluator::from_expr(expr.clone(), 2, domain, coarse_to_fine).unwrap();
+                    while !ev.advance(97).unwrap() {}
+                    assert_eq!(ev.progress(), 1.);
+                    let done = ev.into_plot().unwrap();
+                    assert_eq!(done.domain(), domain);
+                    assert_eq!(bits(done.samples()), bits(plot.samples()), "{}", input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_stops_progress() {
+        let expr = ShuntingParser::parse_str("z").unwrap();
+        let mut ev = Evaluator::from_expr(expr, 2, Domain::Plane, true).unwrap();
+        ev.advance(10).unwrap();
+        let count = ev.count;
+        ev.cancel();
+        assert!(!ev.advance(1000000).unwrap());
+        assert_eq!(ev.count, count);
+        assert!(!ev.is_done());
+        assert!(ev.into_plot().is_err());
+    }
+
+    #[test]
+    fn preview_borrows_evaluated_samples() {
+        // f(z) = z tells the samples apart, so each preview value names the
+        // sample it was taken from
+        let expr = ShuntingParser::parse_str("z").unwrap();
+        let full = Plot::sample(&expr, 2).unwrap();
+        let mut ev = Evaluator::from_expr(expr, 2, Domain::Plane, true).unwrap();
+        while ev.pass == 0 {
+            ev.advance(1).unwrap();
+        }
+        let side = ev.side;
+        let preview = ev.preview_plot();
+        for i in 0..side {
+            for j in 0..side {
+                let w = preview.samples()[i * side + j];
+                let source = (0..side * side).find(|&k| {
+                    let (ci, cj) = (k / side, k % side);
+                    ev.evaluated[k]
+                        && full.samples()[k] == w
+                        && ci.abs_diff(i) < 8
+                        && cj.abs_diff(j) < 8
+                });
+                assert!(source.is_some(), "({}, {}) -> {}", i, j, w);
+            }
+        }
+    }
+}
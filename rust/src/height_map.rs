@@ -0,0 +1,100 @@
+use crate::stats;
+use std::f32::consts::{E, PI};
+
+// how the plotted quantity (Re, Im or |f|) is turned into the height of the
+// surface. every mapping is odd (h -> -h flips the result) so Re/Im keep
+// their sign and |f| stays non-negative
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeightMap {
+    // the value itself
+    Linear,
+    // sign(h) * log_base(1 + |h|)
+    Log(f32),
+    // compresses into (-range, range) with an arctan, linear near 0
+    Atan(f32),
+    // compresses into (-range, range) with a tanh, linear near 0
+    Tanh(f32),
+    // sign(h) * |h|^p
+    Power(f32),
+    // cut off at ±limit
+    Clamp(f32),
+    // scales the whole grid so the given percentile of |h| ends up at
+    // `height`, anything beyond is clamped to ±height
+    Auto { percentile: f32, height: f32 },
+}
+
+impl HeightMap {
+    // accepts "linear", "log[:base]", "atan[:range]", "tanh[:range]",
+    // "power:<p>", "clamp:<limit>" and "auto[:percentile[:height]]"
+    pub fn parse(spec: &str) -> Result<HeightMap, String> {
+        let spec = spec.trim().to_lowercase();
+        let mut parts = spec.split(':').map(|s| s.trim());
+        let name = parts.next().unwrap_or("");
+        let mut args = Vec::new();
+        for arg in parts {
+            args.push(
+                arg.parse::<f32>()
+                    .map_err(|_| format!("Bad height parameter: {}", arg))?,
+            );
+        }
+        let map = match (name, &args[..]) {
+            ("linear" | "", []) => HeightMap::Linear,
+            ("log", []) => HeightMap::Log(E),
+            ("log", [base]) if *base > 1. => HeightMap::Log(*base),
+            ("atan", []) => HeightMap::Atan(10.),
+            ("atan", [range]) if *range > 0. => HeightMap::Atan(*range),
+            ("tanh", []) => HeightMap::Tanh(10.),
+            ("tanh", [range]) if *range > 0. => HeightMap::Tanh(*range),
+            ("power", [p]) if *p > 0. => HeightMap::Power(*p),
+            ("clamp", [limit]) if *limit > 0. => HeightMap::Clamp(*limit),
+            ("auto", []) => HeightMap::Auto {
+                percentile: 95.,
+                height: 10.,
+            },
+            ("auto", [percentile]) if (0. ..=100.).contains(percentile) => HeightMap::Auto {
+                percentile: *percentile,
+                height: 10.,
+            },
+            ("auto", [percentile, height]) if (0. ..=100.).contains(percentile) && *height > 0. => {
+                HeightMap::Auto {
+                    percentile: *percentile,
+                    height: *height,
+                }
+            }
+            _ => return Err(format!("Unknown height mapping: {}", spec)),
+        };
+        Ok(map)
+    }
+
+    // maps every height in place. Auto looks at the whole slice first, the
+    // others map each value on its own
+    pub fn apply(&self, heights: &mut [f32]) {
+        let map = match *self {
+            HeightMap::Auto { percentile, height } => {
+                let abs: Vec<f32> = heights.iter().map(|h| h.abs()).collect();
+                let p = stats::percentile(&abs, percentile);
+                let scale = if p > 0. { height / p } else { 1. };
+                for h in heights.iter_mut() {
+                    *h = (*h * scale).clamp(-height, height);
+                }
+                return;
+            }
+            map => map,
+        };
+        for h in heights.iter_mut() {
+            *h = map.map(*h);
+        }
+    }
+
+    // a single value, Auto is treated as Linear since it needs the whole grid
+    pub fn map(&self, h: f32) -> f32 {
+        match *self {
+            HeightMap::Linear | HeightMap::Auto { .. } => h,
+            HeightMap::Log(base) => h.signum() * h.abs().ln_1p() / base.ln(),
+            HeightMap::Atan(range) => range * (h / range).atan() * 2. / PI,
+            HeightMap::Tanh(range) => range * (h / range).tanh(),
+            HeightMap::Power(p) => h.signum() * h.abs().powf(p),
+            HeightMap::Clamp(limit) => h.clamp(-limit, limit),
+        }
+    }
+}
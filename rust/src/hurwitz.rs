@@ -0,0 +1,600 @@
+use crate::rpneval::{euler_maclaurin, ln_gamma, polygamma, zeta};
+use num_complex::Complex64;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+// left of this Hurwitz's formula takes over from Euler-Maclaurin summation
+// for real a, whose terms would cancel badly
+const REFLECT_BELOW: f64 = -5.;
+// between REFLECT_BELOW and 0 they still cancel, there Hurwitz's series goes
+// through the polylogarithm's expansion instead, as long as |Im s| stays below
+// this (further up the expansion's own terms grow like e^(|Im s| / 2))
+const POLYLOG_WITHIN: f64 = 10.;
+// the Lerch series is summed directly inside this radius
+const DIRECT_RADIUS: f64 = 0.5;
+// the largest modulus dirichlet_l takes: L(s, χ) costs q Hurwitz zetas (and
+// the table q complex numbers), some 20 ms per point up here
+const MAX_MODULUS: u32 = 10_000;
+// characters kept per thread, far more than an expression asks for
+const CACHED_CHARACTERS: usize = 16;
+
+// χ(0), ..., χ(q-1), None for a k that isn't coprime to q
+type Table = Option<Rc<Vec<Complex64>>>;
+
+thread_local! {
+    // the tables for the (q, k) seen lately, since dirichlet_l is evaluated
+    // over and over at the same character
+    static CHARACTERS: RefCell<HashMap<(u32, u32), Table>> = RefCell::new(HashMap::new());
+}
+
+fn infinity() -> Complex64 {
+    Complex64::new(f64::INFINITY, 0.)
+}
+
+fn nan() -> Complex64 {
+    Complex64::new(f64::NAN, f64::NAN)
+}
+
+fn is_integer(x: Complex64) -> bool {
+    x.im == 0. && x.re == x.re.floor()
+}
+
+// --------------------------------------------------------------
+// hurwitz zeta
+// --------------------------------------------------------------
+// ζ(s, a) = Σ_{k>=0} (a + k)^-s, with its pole at s = 1 (for every a)
+pub fn hurwitz_zeta(s: Complex64, a: Complex64) -> Complex64 {
+    if s == Complex64::new(1., 0.) {
+        return infinity();
+    }
+    if is_integer(a) && a.re <= 0. {
+        // one of the terms is 0^-s
+        if s.re > 0. {
+            return infinity();
+        }
+        let zero_term = if s.re == 0. && s.im == 0. { 1. } else { 0. };
+        let mut sum = hurwitz_zeta(s, Complex64::new(1., 0.)) + zero_term;
+        for k in (a.re as i64)..0 {
+            sum += (-s * Complex64::new(k as f64, 0.).ln()).exp();
+        }
+        return sum;
+    }
+    if a == Complex64::new(1., 0.) {
+        return zeta(s);
+    }
+    if a.im == 0. && (s.re < REFLECT_BELOW || (s.re < 0. && s.im.abs() <= POLYLOG_WITHIN)) {
+        // into (0, 1]: ζ(s, a) = ζ(s, a + m) + Σ_{k<m} (a + k)^-s
+        let m = 1. - a.re.ceil();
+        let mut sum = hurwitz_reflect(s, a.re + m);
+        for k in 0..m.abs() as i64 {
+            let (k, sign) = if m > 0. {
+                (a.re + k as f64, 1.)
+            } else {
+                (a.re + m + k as f64, -1.)
+            };
+            sum += (-s * Complex64::new(k, 0.).ln()).exp() * sign;
+        }
+        return sum;
+    }
+    // far enough out for the corrections to shrink like ((|s| + 2j) / 2π|w|)^2
+    let n = (3. * (s.norm() + 20.) / (2. * PI) - a.re).ceil().max(0.) as usize;
+    euler_maclaurin(s, a, n)
+}
+
+// Hurwitz's formula for 0 < a <= 1:
+// ζ(s, a) = 2 Γ(1-s) / (2π)^(1-s) Σ_{n>=1} sin(πs/2 + 2πna) n^(s-1)
+fn hurwitz_reflect(s: Complex64, a: f64) -> Complex64 {
+    let i = Complex64::new(0., 1.);
+    let x = 2f64.ln() + ln_gamma(1. - s) - (1. - s) * (2. * PI).ln();
+    // the sine split into its exponentials, each with the prefactor, so that
+    // only the one that matters can overflow
+    let up = (x + i * s * (PI / 2.)).exp();
+    let down = (x - i * s * (PI / 2.)).exp();
+    let rotation = Complex64::from_polar(1., 2. * PI * a);
+    let (mut sum_up, mut sum_down) = (Complex64::new(0., 0.), Complex64::new(0., 0.));
+    if s.re < REFLECT_BELOW {
+        let mut turn = Complex64::new(1., 0.);
+        for n in 1..100_000 {
+            turn *= rotation;
+            let power = ((s - 1.) * (n as f64).ln()).exp();
+            sum_up += power * turn;
+            sum_down += power / turn;
+            if power.norm() < 1e-17 * sum_up.norm().max(sum_down.norm()) {
+                break;
+            }
+        }
+    } else if a != 1. {
+        // the sums are Li_(1-s)(e^(±2πia)) = z Φ(z, 1-s, 1), whose expansion
+        // only needs ζ(1-s-r)
+        sum_up = rotation * lerch_expansion(rotation, 1. - s, 1.);
+        sum_down = rotation.conj() * lerch_expansion(rotation.conj(), 1. - s, 1.);
+    } else {
+        sum_up = zeta(1. - s);
+        sum_down = sum_up;
+    }
+    let value = (up * sum_up - down * sum_down) / (2. * i);
+    if value.is_nan() && !(up.is_finite() && down.is_finite()) {
+        return infinity();
+    }
+    value
+}
+
+// --------------------------------------------------------------
+// lerch transcendent
+// --------------------------------------------------------------
+// Φ(z, s, a) = Σ_{k>=0} z^k (a + k)^-s, continued analytically past the unit
+// circle (for real a, as far as |ln z| < 2π)
+pub fn lerch(z: Complex64, s: Complex64, a: Complex64) -> Complex64 {
+    if z == Complex64::new(1., 0.) {
+        return hurwitz_zeta(s, a);
+    }
+    if is_integer(a) && a.re <= 0. {
+        return infinity();
+    }
+    if z.norm() <= DIRECT_RADIUS {
+        return lerch_series(z, s, a);
+    }
+    if a.im == 0. && z.ln().norm() < 2. * PI {
+        // into (0, 1]: Φ(z, s, a) = Σ_{k<m} z^k (a + k)^-s + z^m Φ(z, s, a + m)
+        let m = 1. - a.re.ceil();
+        let shifted = lerch_expansion(z, s, a.re + m);
+        let mut head = Complex64::new(0., 0.);
+        let lower = a.re.min(a.re + m);
+        let mut z_k = Complex64::new(1., 0.);
+        for k in 0..m.abs() as i32 {
+            head += z_k * (-s * Complex64::new(lower + k as f64, 0.).ln()).exp();
+            z_k *= z;
+        }
+        let z_m = z.powi(m as i32);
+        return if m >= 0. {
+            head + z_m * shifted
+        } else {
+            // m < 0: Φ(z, s, a + m) = head + z^-m Φ(z, s, a)
+            (shifted - head) * z_m
+        };
+    }
+    if z.norm() < 1. {
+        return lerch_series(z, s, a);
+    }
+    nan()
+}
+
+// the defining series, for |z| < 1
+fn lerch_series(z: Complex64, s: Complex64, a: Complex64) -> Complex64 {
+    let ln_r = z.norm().ln();
+    // for Re s < 0 the terms grow until |z|^k k^-Re s peaks
+    let peak = (-s.re / -ln_r).max(0.);
+    let mut sum = Complex64::new(0., 0.);
+    let mut z_k = Complex64::new(1., 0.);
+    for k in 0..1_000_000 {
+        let term = z_k * (-s * (a + k as f64).ln()).exp();
+        sum += term;
+        if k as f64 > peak && term.norm() < 1e-17 * sum.norm() {
+            break;
+        }
+        z_k *= z;
+    }
+    sum
+}
+
+// around z = 1, for 0 < a <= 1 and |ln z| < 2π:
+// Φ(z, s, a) = z^-a [Γ(1-s) (-ln z)^(s-1) + Σ_{r>=0} ζ(s-r, a) (ln z)^r / r!]
+// for s = 1, 2, 3, ... the Γ pole cancels against the one of ζ(1, a) in the
+// r = s - 1 term, which becomes (ln z)^r / r! (ψ(s) - ψ(a) - ln(-ln z))
+fn lerch_expansion(z: Complex64, s: Complex64, a: f64) -> Complex64 {
+    let ln_z = z.ln();
+    let a64 = Complex64::new(a, 0.);
+    let positive_integer = is_integer(s) && s.re >= 1.;
+    let mut sum = if positive_integer {
+        Complex64::new(0., 0.)
+    } else {
+        (ln_gamma(1. - s) + (s - 1.) * (-ln_z).ln()).exp()
+    };
+    let mut power = Complex64::new(1., 0.);
+    let mut small = 0;
+    for r in 0..400 {
+        let term = if positive_integer && r as f64 == s.re - 1. {
+            let m = s.re as u32;
+            power * (polygamma(Complex64::new(m as f64, 0.), 0) - polygamma(a64, 0) - (-ln_z).ln())
+        } else {
+            power * hurwitz_zeta(s - r as f64, a64)
+        };
+        sum += term;
+        // ζ(s - r, a) has zeroes (like ζ at the negative even integers), one
+        // small term isn't enough
+        if term.norm() < 1e-17 * sum.norm() {
+            small += 1;
+            if small == 2 {
+                break;
+            }
+        } else {
+            small = 0;
+        }
+        power *= ln_z / (r + 1) as f64;
+    }
+    (-a * ln_z).exp() * sum
+}
+
+// --------------------------------------------------------------
+// dirichlet L-functions
+// --------------------------------------------------------------
+// L(s, χ) = Σ χ(n) n^-s = q^-s Σ_{n=1}^q χ(n) ζ(s, n/q) for the Dirichlet
+// character χ mod q with Conrey label k (as in the LMFDB): k = 1 is the
+// principal character, k has to be coprime to q. NaN for other k, an error for
+// q above MAX_MODULUS
+pub fn dirichlet_l(s: Complex64, q: u32, k: u32) -> Result<Complex64, String> {
+    if q > MAX_MODULUS {
+        return Err(format!("dirichlet_l: modulus {} above {}", q, MAX_MODULUS));
+    }
+    let chi = match cached_character(q, k) {
+        Some(chi) => chi,
+        None => return Ok(nan()),
+    };
+    if q == 1 {
+        return Ok(zeta(s));
+    }
+    let qf = q as f64;
+    if s == Complex64::new(1., 0.) {
+        if k % q == 1 {
+            return Ok(infinity());
+        }
+        // the poles of ζ(s, n/q) cancel: L(1, χ) = -Σ χ(n) ψ(n/q) / q
+        let mut sum = Complex64::new(0., 0.);
+        for (n, c) in chi.iter().enumerate().skip(1) {
+            if c.norm() > 0. {
+                sum -= c * polygamma(Complex64::new(n as f64 / qf, 0.), 0);
+            }
+        }
+        return Ok(sum / qf);
+    }
+    let mut sum = Complex64::new(0., 0.);
+    for (n, c) in chi.iter().enumerate().skip(1) {
+        if c.norm() > 0. {
+            sum += c * hurwitz_zeta(s, Complex64::new(n as f64 / qf, 0.));
+        }
+    }
+    Ok(sum * (-s * qf.ln()).exp())
+}
+
+// character(q, k), built once per thread
+fn cached_character(q: u32, k: u32) -> Table {
+    CHARACTERS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(chi) = cache.get(&(q, k)) {
+            return chi.clone();
+        }
+        if cache.len() >= CACHED_CHARACTERS {
+            cache.clear();
+        }
+        let chi = character(q, k).map(Rc::new);
+        cache.insert((q, k), chi.clone());
+        chi
+    })
+}
+
+// χ(n) for n = 0..q of the character mod q with Conrey label k: the product
+// over the prime powers p^e dividing q of
+//     e(log_g(k) log_g(n) / φ(p^e))                                 odd p
+//     e((1 - ε(k))(1 - ε(n)) / 8 + log_5(ε(k) k) log_5(ε(n) n) / 2^(e-2))  p = 2
+// with e(x) = exp(2πix), g the least primitive root mod p^2 and ε(n) = ±1
+// with ε(n) n = 1 mod 4
+pub fn character(q: u32, k: u32) -> Option<Vec<Complex64>> {
+    if q == 0 || gcd(k as u64, q as u64) != 1 {
+        return None;
+    }
+    let q = q as u64;
+    let mut chi = vec![Complex64::new(1., 0.); q as usize];
+    if q > 1 {
+        chi[0] = Complex64::new(0., 0.);
+    }
+    for (p, e) in factorize(q) {
+        let pe = p.pow(e);
+        // the exponent of e(x) as a fraction of the group order per residue
+        let (order, phase): (u64, Vec<Option<u64>>) = if p == 2 {
+            prime_power_two(k as u64 % pe, e)
+        } else {
+            let phi = pe / p * (p - 1);
+            let g = primitive_root(p);
+            let mut log = vec![None; pe as usize];
+            let mut x = 1;
+            for j in 0..phi {
+                log[x as usize] = Some(j);
+                x = x * g % pe;
+            }
+            let log_k = log[(k as u64 % pe) as usize].unwrap_or(0);
+            let phase = log.iter().map(|l| l.map(|l| l * log_k % phi)).collect();
+            (phi, phase)
+        };
+        for (n, c) in chi.iter_mut().enumerate() {
+            match phase[n % pe as usize] {
+                Some(x) => *c *= Complex64::from_polar(1., 2. * PI * x as f64 / order as f64),
+                None => *c = Complex64::new(0., 0.),
+            }
+        }
+    }
+    Some(chi)
+}
+
+// the p = 2 factor, as phases out of 2^e (None for even n)
+fn prime_power_two(k: u64, e: u32) -> (u64, Vec<Option<u64>>) {
+    let pe = 1u64 << e;
+    if e == 1 {
+        return (1, vec![None, Some(0)]);
+    }
+    // ε(n) and log_5(ε(n) n) mod 2^e, 5 generates the residues = 1 mod 4
+    let mut log5 = vec![0; pe as usize];
+    let mut x = 1;
+    for j in 0..pe / 4 {
+        log5[x as usize] = j;
+        x = x * 5 % pe;
+    }
+    let split = |n: u64| {
+        if n % 4 == 1 {
+            (false, log5[n as usize])
+        } else {
+            (true, log5[(pe - n) as usize])
+        }
+    };
+    let (k_negative, k_log) = split(k);
+    let phase = (0..pe)
+        .map(|n| {
+            if n % 2 == 0 {
+                return None;
+            }
+            let (n_negative, n_log) = split(n);
+            // (1 - ε)(1 - ε) / 8 is 1/2 when both are -1, that's 2^(e-1) / 2^e
+            let sign = if k_negative && n_negative { pe / 2 } else { 0 };
+            Some((sign + 4 * (k_log * n_log % (pe / 4))) % pe)
+        })
+        .collect();
+    (pe, phase)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// (p, e) for each prime power in n
+fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        let mut e = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            e += 1;
+        }
+        if e > 0 {
+            factors.push((p, e));
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    base %= modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result
+}
+
+// the least primitive root mod p^2 of an odd prime p, which is one mod every
+// power of p
+fn primitive_root(p: u64) -> u64 {
+    let factors = factorize(p - 1);
+    (2..)
+        .find(|&g| {
+            factors
+                .iter()
+                .all(|&(r, _)| pow_mod(g, (p - 1) / r, p) != 1)
+                && pow_mod(g, p - 1, p * p) != 1
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    fn close(got: Complex64, want: Complex64, tol: f64) {
+        assert!(
+            (got - want).norm() <= tol * want.norm(),
+            "got {}, want {}",
+            got,
+            want
+        );
+    }
+
+    #[test]
+    fn hurwitz_values() {
+        // ζ(2, 1/2) = π²/2
+        close(
+            hurwitz_zeta(c(2., 0.), c(0.5, 0.)),
+            c(PI * PI / 2., 0.),
+            1e-14,
+        );
+        close(
+            hurwitz_zeta(c(2.5, 1.), c(0.3, 0.)),
+            c(7.852880705201321, 18.59314353431856),
+            1e-13,
+        );
+        close(
+            hurwitz_zeta(c(0.5, 10.), c(2.7, 0.)),
+            c(-0.3308365508192671, 0.11157871470698809),
+            1e-12,
+        );
+        close(
+            hurwitz_zeta(c(3., -1.), c(0.5, 0.5)),
+            c(-0.9485970611623291, -0.581654069269602),
+            1e-13,
+        );
+        // complex a stays with Euler-Maclaurin, whose terms cancel here
+        close(
+            hurwitz_zeta(c(-3.5, 2.), c(1., -2.)),
+            c(-0.24056378135773465, 0.3001369611499515),
+            1e-10,
+        );
+        // Hurwitz's formula through the polylogarithm
+        close(
+            hurwitz_zeta(c(-4.9, 0.), c(0.3, 0.)),
+            c(0.0018374700689571624, 0.),
+            1e-13,
+        );
+        close(
+            hurwitz_zeta(c(-2.5, 3.), c(2.7, 0.)),
+            c(-0.2641515122112454, 3.4502378984314053),
+            1e-13,
+        );
+        // Hurwitz's formula, then shifted back from (0, 1]
+        close(
+            hurwitz_zeta(c(-12.5, 3.), c(0.3, 0.)),
+            c(-0.37484987409189074, -2.235372477494336),
+            1e-12,
+        );
+        close(
+            hurwitz_zeta(c(-7.5, 2.), c(-1.4, 0.)),
+            c(-4162.479977476208, -5222.77036173822),
+            1e-12,
+        );
+        // a term 0^-s among the rest
+        close(hurwitz_zeta(c(-2., 0.), c(-3., 0.)), c(14., 0.), 1e-14);
+        assert_eq!(hurwitz_zeta(c(2., 0.), c(-3., 0.)), infinity());
+        assert_eq!(hurwitz_zeta(c(1., 0.), c(0.3, 0.)), infinity());
+    }
+
+    #[test]
+    fn lerch_values() {
+        // the direct series
+        close(
+            lerch(c(0.3, 0.2), c(2., 0.), c(0.5, 0.)),
+            c(4.139758577751146, 0.11244067791989187),
+            1e-14,
+        );
+        close(
+            lerch(c(0.5, 0.5), c(2., 0.), c(0.5, 1.)),
+            c(-0.2521435435752034, -0.6479483543769103),
+            1e-13,
+        );
+        close(
+            lerch(c(0.7, 0.), c(2., 0.), c(-1.5, 0.)),
+            c(5.42055892861198, 0.),
+            1e-13,
+        );
+        // the expansion in ln z, inside and out of the unit disc
+        close(
+            lerch(c(-0.9, 0.), c(3., 0.), c(1., 0.)),
+            c(0.9095980017159598, 0.),
+            1e-13,
+        );
+        close(
+            lerch(c(0.9, 0.3), c(2.5, 1.), c(0.3, 0.)),
+            c(7.968895972472668, 18.940501534439893),
+            1e-12,
+        );
+        close(
+            lerch(c(3., 4.), c(-1.5, 3.), c(1.7, 0.)),
+            c(-5.42738500778184, -6.218909084220354),
+            1e-12,
+        );
+        close(
+            lerch(c(-5., 1.), c(0.5, -2.), c(3., 0.)),
+            c(-0.022803608096482273, 0.11278756606086095),
+            1e-12,
+        );
+        close(
+            lerch(c(1.5, -0.5), c(-2., 0.), c(0.25, 0.)),
+            c(3.4375, -9.5625),
+            1e-13,
+        );
+    }
+
+    #[test]
+    fn dirichlet_values() {
+        // L(1, χ_4) = π/4 and L(2, χ_4) = Catalan's constant
+        close(dirichlet_l(c(1., 0.), 4, 3).unwrap(), c(PI / 4., 0.), 1e-14);
+        close(
+            dirichlet_l(c(2., 0.), 4, 3).unwrap(),
+            c(0.915965594177219, 0.),
+            1e-14,
+        );
+        // L(1, χ_3) = π/3√3
+        close(
+            dirichlet_l(c(1., 0.), 3, 2).unwrap(),
+            c(PI / (3. * 3f64.sqrt()), 0.),
+            1e-14,
+        );
+        // complex characters
+        close(
+            dirichlet_l(c(3., 0.), 5, 2).unwrap(),
+            c(0.9881916816240572, 0.08910518834573959),
+            1e-13,
+        );
+        close(
+            dirichlet_l(c(0.5, 14.), 5, 2).unwrap(),
+            c(1.0900647595864155, -0.6940153047059727),
+            1e-12,
+        );
+        close(
+            dirichlet_l(c(1., 0.), 7, 3).unwrap(),
+            c(0.8042057293867807, 0.39866669881887307),
+            1e-13,
+        );
+        close(
+            dirichlet_l(c(2., 1.), 7, 3).unwrap(),
+            c(1.067968570528764, 0.2559734178123215),
+            1e-13,
+        );
+        close(
+            dirichlet_l(c(0.5, 6.), 12, 5).unwrap(),
+            c(1.5685152983540738, 0.33427746172150463),
+            1e-12,
+        );
+        close(
+            dirichlet_l(c(-2.5, 1.), 8, 3).unwrap(),
+            c(-9.304692233424271, 6.437885631600497),
+            1e-12,
+        );
+        close(
+            dirichlet_l(c(2., 0.), 1, 1).unwrap(),
+            c(PI * PI / 6., 0.),
+            1e-14,
+        );
+        assert_eq!(dirichlet_l(c(1., 0.), 5, 1).unwrap(), infinity());
+        assert!(dirichlet_l(c(2., 0.), 6, 3).unwrap().re.is_nan());
+        assert!(dirichlet_l(c(2., 0.), MAX_MODULUS, 1).is_ok());
+        assert!(dirichlet_l(c(2., 0.), MAX_MODULUS + 1, 1).is_err());
+    }
+
+    #[test]
+    fn characters() {
+        let i = c(0., 1.);
+        let chi = character(5, 2).unwrap();
+        for (got, want) in chi.iter().zip([c(0., 0.), c(1., 0.), i, -i, c(-1., 0.)]) {
+            assert!((got - want).norm() < 1e-15, "got {}, want {}", got, want);
+        }
+        assert!(character(6, 3).is_none());
+        // the cache hands back the same table
+        let first = cached_character(7, 3).unwrap();
+        assert!(Rc::ptr_eq(&first, &cached_character(7, 3).unwrap()));
+    }
+}
@@ -14,6 +14,83 @@ pub use crate::parser::{precedence, RPNExpr, ShuntingParser};
 mod rpneval;
 pub use crate::rpneval::MathContext;
 
+#[path = "./airy.rs"]
+mod airy;
+#[path = "./bessel.rs"]
+mod bessel;
+#[path = "./elliptic.rs"]
+mod elliptic;
+#[path = "./erf.rs"]
+mod erf;
+#[path = "./hurwitz.rs"]
+mod hurwitz;
+#[path = "./weierstrass.rs"]
+mod weierstrass;
+
+#[path = "./simd.rs"]
+mod simd;
+
+#[path = "./batch.rs"]
+mod batch;
+pub use crate::batch::LANES;
+
+#[path = "./colormap.rs"]
+mod colormap;
+pub use crate::colormap::{AlphaMap, ColorStop, ColorStyle, Colormap, UNDEFINED_COLOR};
+
+#[path = "./plot_result.rs"]
+mod plot_result;
+pub use crate::plot_result::{PlotResult, VertexKind};
+
+#[path = "./domain.rs"]
+mod domain;
+pub use crate::domain::Domain;
+
+#[path = "./plot.rs"]
+mod plot;
+pub use crate::plot::Plot;
+
+#[path = "./height_map.rs"]
+mod height_map;
+pub use crate::height_map::HeightMap;
+
+#[path = "./stats.rs"]
+mod stats;
+pub use crate::stats::{PlotStats, Summary};
+
+#[path = "./evaluator.rs"]
+mod evaluator;
+pub use crate::evaluator::Evaluator;
+
+#[path = "./polyline.rs"]
+mod polyline;
+pub use crate::polyline::Polylines;
+
+#[path = "./vector_field.rs"]
+mod vector_field;
+pub use crate::vector_field::VectorField;
+
+#[path = "./conformal.rs"]
+mod conformal;
+pub use crate::conformal::{conformal_grid, map_curves, Curve};
+
+#[path = "./contour.rs"]
+mod contour;
+pub use crate::contour::{level_set, Quantity};
+
+#[path = "./png.rs"]
+mod png;
+
+#[path = "./svg.rs"]
+mod svg;
+pub use crate::svg::SvgExport;
+
+// with the parallel feature on wasm, JS starts rayon's web workers with
+// `await initThreadPool(navigator.hardwareConcurrency)` before the first Plot.
+// until then (or without SharedArrayBuffer) rayon runs on the calling thread
+#[cfg(all(feature = "parallel", target_arch = "wasm32"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
 pub use num_complex::Complex32;
 pub use std::f32::consts::{E, PI};
 pub use std::vec::Vec;
@@ -38,187 +115,70 @@ extern "C" {
 }
 
 #[wasm_bindgen]
-pub fn evaluate(input: &str, n: i16, graph_type: u8, log_height: bool) -> Vec<f32> {
-    let expr = ShuntingParser::parse_str(input).unwrap();
-    let ctx = MathContext::new();
-    let mut pos = Vec::new();
-    let mut color = Vec::new();
-    let saturation = 0.75;
-    let num = n * 10;
-    for i in -num..=num {
-        for j in -num..=num {
-            let re = i as f32 / n as f32;
-            let im = j as f32 / n as f32;
-            pos.push(re);
-            pos.push(im);
-            let result = ctx.eval(&expr, Complex32::new(re, im)).unwrap();
-            // settles height
-            match graph_type {
-                // Re-Im, height = real component
-                0 | 1 => {
-                    let height = remove_inf(result.re);
-                    if log_height {
-                        if height == 0. {
-                            pos.push(0.);
-                        } else if height > 0. {
-                            pos.push((1. + height).ln());
-                        } else {
-                            pos.push(-(1. - height).ln());
-                        }
-                    } else {
-                        pos.push(height);
-                    }
-                }
-                // Im-Re, height = imaginary component
-                2 | 3 => {
-                    let height = remove_inf(result.im);
-                    if log_height {
-                        if height == 0. {
-                            pos.push(0.);
-                        } else if height > 0. {
-                            pos.push((1. + height).ln());
-                        } else {
-                            pos.push(-(1. - height).ln());
-                        }
-                    } else {
-                        pos.push(height);
-                    }
-                }
-                // Mod-Arg, height = modulus
-                _ => {
-                    let height = remove_inf(result.norm());
-                    if log_height {
-                        pos.push((1.0 + height).ln());
-                    } else {
-                        pos.push(height);
-                    }
-                }
-            }
-            // settles color
-            match graph_type {
-                // Re-Im, color
-                0 => {
-                    //log(format!("{:?}", result.im).as_str());
-                    let mut color_vec = gradient(result.im);
-                    color.append(&mut color_vec);
-                }
-                // Re-Im, BW
-                1 => {
-                    let sig_im = sig(result.im);
-                    color.push(sig_im);
-                    color.push(sig_im);
-                    color.push(sig_im);
-                }
-                // Im-Re, color
-                2 => {
-                    let mut color_vec = gradient(result.re);
-                    color.append(&mut color_vec);
-                }
-                // Im-Re, BW
-                3 => {
-                    let sig_re = sig(result.re);
-                    color.push(sig_re);
-                    color.push(sig_re);
-                    color.push(sig_re);
-                }
-                // Mod-Arg
-                _ => {
-                    let mut color_vec = hsvcolor(result.arg(), saturation, 1.);
-                    color.append(&mut color_vec);
-                }
-            }
-        }
-    }
-    pos.append(&mut color); // add color array to the position array (thats just how threejs works)
-    return pos;
+pub fn evaluate(
+    input: &str,
+    n: i16,
+    graph_type: u8,
+    log_height: bool,
+) -> Result<Vec<f32>, JsValue> {
+    let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+    let style = ColorStyle::default_for(graph_type);
+    let height_map = if log_height {
+        HeightMap::Log(E)
+    } else {
+        HeightMap::Linear
+    };
+    // only the positions and colours, not the rest of a PlotResult
+    let plot = Plot::sample(&expr, n).map_err(|e| JsValue::from_str(&e))?;
+    let heights = plot.heights(graph_type, &height_map);
+    let mask = plot.mask(&heights);
+    let mut result = plot.positions(&heights, &mask);
+    result.extend(plot.colors(graph_type, &style));
+    Ok(result)
 }
 
-fn remove_inf(val: f32) -> f32 {
-    if val.is_nan() {
-        return 1E+38; // handles NaN
-    } else if val.is_finite() {
-        return val; // handles normal values
-    } else if val.is_sign_positive() {
-        return 1E+38; // handles positive infinity
-    }
-    return -1E+38; // handles negative infinity
+// like evaluate, but the height mapping (see HeightMap::parse, "log" is
+// evaluate's log_height), the colormap (by name or as gradient stops, see
+// Colormap::parse) and the saturation/value/opacity adjustments are chosen by
+// the caller. an empty `alpha` keeps the colours rgb, otherwise they are rgba
+// with the alpha channel driven by AlphaMap::parse(alpha). positions, colours
+// etc. come back as separate buffers in a PlotResult
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_styled(
+    input: &str,
+    n: i16,
+    graph_type: u8,
+    height_map: &str,
+    colormap: &str,
+    saturation: f32,
+    value: f32,
+    opacity: f32,
+    alpha: &str,
+) -> Result<PlotResult, JsValue> {
+    let plot = Plot::new(input, n)?;
+    plot.render_js(
+        graph_type, height_map, colormap, saturation, value, opacity, alpha,
+    )
 }
 
-// accepts h in radians
-fn hsvcolor(mut h: f32, s: f32, v: f32) -> Vec<f32> {
-    // neg check
-    if h < 0. {
-        h = h % (2. * PI);
-        h += 2. * PI;
-    }
-    // convert to degrees
-    h = h * 180. / PI;
-    let c = s * v;
-    let i = h / 60.;
-    let x = c * (1. - (i % 2. - 1.).abs());
-
-    let r1: f32;
-    let g1: f32;
-    let b1: f32;
-
-    match i.floor() as i8 {
-        0 => {
-            r1 = c;
-            g1 = x;
-            b1 = 0.;
-        }
-        1 => {
-            r1 = x;
-            g1 = c;
-            b1 = 0.;
-        }
-        2 => {
-            r1 = 0.;
-            g1 = c;
-            b1 = x;
-        }
-        3 => {
-            r1 = 0.;
-            g1 = x;
-            b1 = c;
-        }
-        4 => {
-            r1 = x;
-            g1 = 0.;
-            b1 = c;
-        }
-        5 => {
-            r1 = c;
-            g1 = 0.;
-            b1 = x;
-        }
-        _ => {
-            r1 = 0.;
-            g1 = 0.;
-            b1 = 0.;
-        }
-    }
-
-    let m = v - c;
-    let mut vec = Vec::new();
-    vec.push(r1 + m);
-    vec.push(g1 + m);
-    vec.push(b1 + m);
-    return vec;
+// the colormap name and saturation evaluate uses for the graph type, so a
+// front end calling evaluate_styled or Plot.render can start from them
+#[wasm_bindgen]
+pub fn default_colormap(graph_type: u8) -> String {
+    colormap::default_colormap(graph_type).0.to_string()
 }
 
-fn gradient(y: f32) -> Vec<f32> {
-    let gradient = colorous::INFERNO;
-    let color = gradient.eval_continuous(sig(y) as f64);
-
-    // the rgb has to be from 0-1
-    let mut vec = Vec::new();
-    vec.push((color.r as f32) / 256.);
-    vec.push((color.g as f32) / 256.);
-    vec.push((color.b as f32) / 256.);
-    return vec;
+#[wasm_bindgen]
+pub fn default_saturation(graph_type: u8) -> f32 {
+    colormap::default_colormap(graph_type).1
 }
 
-fn sig(x: f32) -> f32 {
-    return 1. / (1. + E.powf(-x / 5.));
+// names of the built-in colormaps, for populating a picker
+#[wasm_bindgen]
+pub fn colormap_names() -> Vec<JsValue> {
+    Colormap::names()
+        .into_iter()
+        .map(JsValue::from_str)
+        .collect()
 }
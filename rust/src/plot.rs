@@ -0,0 +1,448 @@
+use crate::colormap::ColorStyle;
+use crate::contour::{level_set, Quantity};
+use crate::domain::{chordal_distance, sphere_height, Domain};
+use crate::height_map::HeightMap;
+use crate::parser::{RPNExpr, ShuntingParser};
+use crate::plot_result::{PlotResult, VertexKind};
+use crate::polyline::Polylines;
+use crate::rpneval::MathContext;
+use crate::stats::PlotStats;
+use num_complex::Complex32;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::f32::consts::{E, PI};
+use wasm_bindgen::prelude::*;
+
+// an evaluated grid of f(z). the samples are kept around so switching plot
+// type, log height or colormap only has to re-derive heights and colours
+// instead of running the function over the whole grid again
+#[wasm_bindgen]
+pub struct Plot {
+    // number of samples along the real axis
+    #[wasm_bindgen(readonly)]
+    pub cols: usize,
+    // number of samples along the imaginary axis
+    #[wasm_bindgen(readonly)]
+    pub rows: usize,
+    // n samples per unit, over [-10, 10] on both axes
+    #[wasm_bindgen(readonly)]
+    pub n: i16,
+    // what the grid points stand for
+    domain: Domain,
+    // f(z), real-major: sample (i, j) is at index i * rows + j
+    samples: Vec<Complex32>,
+}
+
+impl Plot {
+    pub fn sample(expr: &RPNExpr, n: i16) -> Result<Plot, String> {
+        Plot::sample_on(expr, n, Domain::Plane)
+    }
+
+    pub fn sample_on(expr: &RPNExpr, n: i16, domain: Domain) -> Result<Plot, String> {
+        let ctx = MathContext::new();
+        let side = grid_side(n)?;
+        let mut samples = vec![Complex32::new(0., 0.); side * side];
+        #[cfg(feature = "parallel")]
+        sample_rows_parallel(&ctx, expr, n, domain, &mut samples, side)?;
+        #[cfg(not(feature = "parallel"))]
+        sample_rows(&ctx, expr, n, domain, &mut samples, side)?;
+        Ok(Plot::from_samples(n, domain, samples))
+    }
+
+    // samples laid out as in sample(), over the same grid (so n >= 1)
+    pub(crate) fn from_samples(n: i16, domain: Domain, samples: Vec<Complex32>) -> Plot {
+        let side = grid_side(n).unwrap();
+        assert_eq!(samples.len(), side * side);
+        Plot {
+            cols: side,
+            rows: side,
+            n,
+            domain,
+            samples,
+        }
+    }
+
+    pub fn domain(&self) -> Domain {
+        self.domain
+    }
+
+    pub fn samples(&self) -> &[Complex32] {
+        &self.samples
+    }
+
+    // the input z of sample (i, j)
+    pub fn z(&self, i: usize, j: usize) -> Complex32 {
+        self.domain.z(grid_z(self.n, i, j))
+    }
+
+    // the plotted quantity of every sample, mapped through height_map. NaN
+    // where f is undefined and ±inf at poles (unless the mapping is bounded)
+    pub fn heights(&self, graph_type: u8, height_map: &HeightMap) -> Vec<f32> {
+        let mut heights: Vec<f32> = self
+            .samples
+            .iter()
+            .map(|&result| height(result, graph_type))
+            .collect();
+        height_map.apply(&mut heights);
+        heights
+    }
+
+    // the VertexKind of every sample, given the heights from heights()
+    pub fn mask(&self, heights: &[f32]) -> Vec<VertexKind> {
+        self.samples
+            .iter()
+            .zip(heights)
+            .map(|(&result, &h)| VertexKind::of(result, h))
+            .collect()
+    }
+
+    // (re, im, height) per sample. every height is finite: poles are moved
+    // to the top (or bottom, for -inf) of the finite heights and undefined
+    // samples get 0, they are meant to be left out of the mesh anyway
+    pub fn positions(&self, heights: &[f32], mask: &[VertexKind]) -> Vec<f32> {
+        let finite = || {
+            heights
+                .iter()
+                .zip(mask)
+                .filter(|(h, &kind)| kind == VertexKind::Finite && h.is_finite())
+                .map(|(h, _)| *h)
+        };
+        let lo = finite().fold(0f32, f32::min);
+        let hi = finite().fold(0f32, f32::max);
+        let mut pos = Vec::with_capacity(3 * self.samples.len());
+        for i in 0..self.cols {
+            for j in 0..self.rows {
+                let k = i * self.rows + j;
+                // the grid point, which is not z on the sphere domain
+                let z = grid_z(self.n, i, j);
+                let h = heights[k];
+                pos.push(z.re);
+                pos.push(z.im);
+                pos.push(match mask[k] {
+                    VertexKind::Finite => h,
+                    VertexKind::Pole if h.is_finite() => h,
+                    VertexKind::Pole if h == f32::NEG_INFINITY => lo,
+                    VertexKind::Pole => hi,
+                    VertexKind::Undefined => 0.,
+                });
+            }
+        }
+        pos
+    }
+
+    // rgb or rgba (see ColorStyle::channels) per sample
+    pub fn colors(&self, graph_type: u8, style: &ColorStyle) -> Vec<f32> {
+        let mut color = Vec::with_capacity(style.channels() * self.samples.len());
+        for &result in self.samples.iter() {
+            // t is the hidden component squashed into [0, 1]
+            let t = match graph_type {
+                0 | 1 => sig(result.im),
+                2 | 3 => sig(result.re),
+                // the argument goes once around the (cyclic) colormap
+                _ => result.arg() / (2. * PI),
+            };
+            let undefined = VertexKind::of(result, 0.) == VertexKind::Undefined;
+            // undefined, or a pole whose hidden component is NaN (inf + NaN i)
+            if undefined || t.is_nan() {
+                color.extend_from_slice(&style.undefined);
+            } else {
+                color.extend_from_slice(&shade(t, graph_type, style));
+            }
+            if let Some(alpha) = style.alpha(result, t) {
+                color.push(alpha);
+            }
+        }
+        color
+    }
+
+    pub fn render(&self, graph_type: u8, height_map: &HeightMap, style: &ColorStyle) -> PlotResult {
+        let mut values = Vec::with_capacity(2 * self.samples.len());
+        for result in self.samples.iter() {
+            values.push(result.re);
+            values.push(result.im);
+        }
+        let heights = self.heights(graph_type, height_map);
+        let mask = self.mask(&heights);
+        let lo = grid_z(self.n, 0, 0);
+        let hi = grid_z(self.n, self.cols - 1, self.rows - 1);
+        PlotResult::new(
+            self.cols,
+            self.rows,
+            (lo.re, hi.re, lo.im, hi.im),
+            self.positions(&heights, &mask),
+            self.colors(graph_type, style),
+            style.channels(),
+            values,
+            &mask,
+        )
+    }
+
+    // level curves of quantity at each of the levels, in the input plane
+    // (at height 0)
+    pub fn contours(&self, quantity: Quantity, levels: &[f32]) -> Polylines {
+        let mut points = Vec::with_capacity(3 * self.samples.len());
+        for i in 0..self.cols {
+            for j in 0..self.rows {
+                let p = grid_z(self.n, i, j);
+                points.extend_from_slice(&[p.re, p.im, 0.]);
+            }
+        }
+        self.level_sets(quantity, levels, &points)
+    }
+
+    // the same curves lying on the surface render() builds
+    pub fn contours_on(
+        &self,
+        quantity: Quantity,
+        levels: &[f32],
+        graph_type: u8,
+        height_map: &HeightMap,
+    ) -> Polylines {
+        let heights = self.heights(graph_type, height_map);
+        let mask = self.mask(&heights);
+        self.level_sets(quantity, levels, &self.positions(&heights, &mask))
+    }
+
+    // the height of every vertex of the surface render() builds
+    pub fn surface_heights(&self, graph_type: u8, height_map: &HeightMap) -> Vec<f32> {
+        let heights = self.heights(graph_type, height_map);
+        let mask = self.mask(&heights);
+        self.positions(&heights, &mask)
+            .chunks(3)
+            .map(|p| p[2])
+            .collect()
+    }
+
+    // the height above the point p of the input plane of the surface with the
+    // vertex heights from surface_heights, bilinear between the grid points
+    // around p (the nearest edge of the grid outside it)
+    pub fn surface_height(&self, heights: &[f32], p: Complex32) -> f32 {
+        let n = self.n as f32;
+        let cell = |x: f32, side: usize| {
+            let x = ((x + 10.) * n).clamp(0., (side - 1) as f32);
+            let k = (x.floor() as usize).min(side - 2);
+            (k, x - k as f32)
+        };
+        let (i, s) = cell(p.re, self.cols);
+        let (j, t) = cell(p.im, self.rows);
+        let h = |i: usize, j: usize| heights[i * self.rows + j];
+        (1. - s) * ((1. - t) * h(i, j) + t * h(i, j + 1))
+            + s * ((1. - t) * h(i + 1, j) + t * h(i + 1, j + 1))
+    }
+
+    fn level_sets(&self, quantity: Quantity, levels: &[f32], points: &[f32]) -> Polylines {
+        let values: Vec<f32> = self.samples.iter().map(|&f| quantity.of(f)).collect();
+        let cyclic = quantity == Quantity::Arg;
+        let mut lines = Polylines::new();
+        for &level in levels {
+            lines.append(&level_set(
+                &values, self.cols, self.rows, points, level, cyclic,
+            ));
+        }
+        lines
+    }
+}
+
+#[wasm_bindgen]
+impl Plot {
+    // evaluates `input` over the grid, n samples per unit
+    #[wasm_bindgen(constructor)]
+    pub fn new(input: &str, n: i16) -> Result<Plot, JsValue> {
+        let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+        Plot::sample(&expr, n).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // like the constructor, over the given domain ("plane" or "sphere")
+    #[wasm_bindgen(js_name = onDomain)]
+    pub fn on_domain(input: &str, n: i16, domain: &str) -> Result<Plot, JsValue> {
+        let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+        let domain = Domain::parse(domain).map_err(|e| JsValue::from_str(&e))?;
+        Plot::sample_on(&expr, n, domain).map_err(|e| JsValue::from_str(&e))
+    }
+
+    // min/max/mean/percentiles of Re, Im and |f| and the number of NaN or
+    // infinite samples
+    pub fn stats(&self) -> PlotStats {
+        PlotStats::of(&self.samples)
+    }
+
+    // re-derives heights and colours from the cached samples, the arguments
+    // are the same as for evaluate_styled
+    #[wasm_bindgen(js_name = render)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_js(
+        &self,
+        graph_type: u8,
+        height_map: &str,
+        colormap: &str,
+        saturation: f32,
+        value: f32,
+        opacity: f32,
+        alpha: &str,
+    ) -> Result<PlotResult, JsValue> {
+        let height_map = HeightMap::parse(height_map).map_err(|e| JsValue::from_str(&e))?;
+        let style = ColorStyle::parse(colormap, saturation, value, opacity, alpha)
+            .map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.render(graph_type, &height_map, &style))
+    }
+
+    // level curves of "re", "im", "abs" or "arg" (in radians) at the given
+    // levels, in the input plane
+    #[wasm_bindgen(js_name = contours)]
+    pub fn contours_js(&self, quantity: &str, levels: Vec<f32>) -> Result<Polylines, JsValue> {
+        let quantity = Quantity::parse(quantity).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.contours(quantity, &levels))
+    }
+
+    // the same curves on the surface of render(graph_type, height_map, ...)
+    #[wasm_bindgen(js_name = contoursOnSurface)]
+    pub fn contours_on_js(
+        &self,
+        quantity: &str,
+        levels: Vec<f32>,
+        graph_type: u8,
+        height_map: &str,
+    ) -> Result<Polylines, JsValue> {
+        let quantity = Quantity::parse(quantity).map_err(|e| JsValue::from_str(&e))?;
+        let height_map = HeightMap::parse(height_map).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.contours_on(quantity, &levels, graph_type, &height_map))
+    }
+}
+
+// every chunk of `side` samples is one row (constant re) of the grid
+#[cfg(any(not(feature = "parallel"), test))]
+fn sample_rows(
+    ctx: &MathContext,
+    expr: &RPNExpr,
+    n: i16,
+    domain: Domain,
+    samples: &mut [Complex32],
+    side: usize,
+) -> Result<(), String> {
+    for (i, row) in samples.chunks_mut(side).enumerate() {
+        sample_row(ctx, expr, n, domain, i, row)?;
+    }
+    Ok(())
+}
+
+// rows are independent so they are split across the rayon pool. each sample
+// is computed exactly as in sample_rows, so the output is bit-identical
+#[cfg(feature = "parallel")]
+fn sample_rows_parallel(
+    ctx: &MathContext,
+    expr: &RPNExpr,
+    n: i16,
+    domain: Domain,
+    samples: &mut [Complex32],
+    side: usize,
+) -> Result<(), String> {
+    samples
+        .par_chunks_mut(side)
+        .enumerate()
+        .try_for_each(|(i, row)| sample_row(ctx, expr, n, domain, i, row))
+}
+
+fn sample_row(
+    ctx: &MathContext,
+    expr: &RPNExpr,
+    n: i16,
+    domain: Domain,
+    i: usize,
+    row: &mut [Complex32],
+) -> Result<(), String> {
+    let zs: Vec<Complex32> = (0..row.len()).map(|j| domain.z(grid_z(n, i, j))).collect();
+    ctx.eval_many(expr, &zs, row)
+}
+
+// number of samples along each axis for n samples per unit over [-10, 10]
+pub(crate) fn grid_side(n: i16) -> Result<usize, String> {
+    if n < 1 {
+        return Err(format!("Need at least 1 sample per unit, got {}", n));
+    }
+    Ok(20 * n as usize + 1)
+}
+
+// the grid point of sample (i, j), which is also its input z on the plane
+pub(crate) fn grid_z(n: i16, i: usize, j: usize) -> Complex32 {
+    let num = n as f32 * 10.;
+    let n = n as f32;
+    Complex32::new((i as f32 - num) / n, (j as f32 - num) / n)
+}
+
+// the plotted quantity, before any HeightMap
+fn height(result: Complex32, graph_type: u8) -> f32 {
+    match graph_type {
+        // Re-Im, height = real component
+        0 | 1 => result.re,
+        // Im-Re, height = imaginary component
+        2 | 3 => result.im,
+        // Riemann sphere, height = vertical coordinate on the sphere
+        5 => sphere_height(result),
+        // Riemann sphere, height = chordal distance from 0
+        6 => chordal_distance(result),
+        // Mod-Arg, height = modulus
+        _ => result.norm(),
+    }
+}
+
+// the colour of hidden component t
+pub(crate) fn shade(t: f32, graph_type: u8, style: &ColorStyle) -> [f32; 3] {
+    match graph_type {
+        // Re-Im/Im-Re, BW
+        1 | 3 => [t, t, t],
+        // Re-Im/Im-Re, color, Mod-Arg and the Riemann sphere
+        _ => style.rgb(t),
+    }
+}
+
+pub(crate) fn sig(x: f32) -> f32 {
+    1. / (1. + E.powf(-x / 5.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::Evaluator;
+
+    #[test]
+    fn rejects_densities_below_1() {
+        let expr = ShuntingParser::parse_str("z").unwrap();
+        for n in [0, -1, i16::MIN] {
+            assert!(Plot::sample(&expr, n).is_err());
+            assert!(Evaluator::from_expr(expr.clone(), n, Domain::Plane, true).is_err());
+        }
+        assert_eq!(Plot::sample(&expr, 1).unwrap().cols, 21);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_serial() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let ctx = MathContext::new();
+        for input in [
+            "z^2 + 1/z",
+            "gamma(z) * sin(z)",
+            "ln(z) / (z - 1)",
+            "zeta(z)",
+        ] {
+            let expr = ShuntingParser::parse_str(input).unwrap();
+            for domain in [Domain::Plane, Domain::Sphere] {
+                let side = grid_side(5).unwrap();
+                let mut serial = vec![Complex32::new(0., 0.); side * side];
+                let mut parallel = serial.clone();
+                sample_rows(&ctx, &expr, 5, domain, &mut serial, side).unwrap();
+                pool.install(|| sample_rows_parallel(&ctx, &expr, 5, domain, &mut parallel, side))
+                    .unwrap();
+                let bits = |w: &[Complex32]| {
+                    w.iter()
+                        .map(|z| (z.re.to_bits(), z.im.to_bits()))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(bits(&serial), bits(&parallel), "{}", input);
+            }
+        }
+    }
+}
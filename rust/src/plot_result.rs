@@ -0,0 +1,255 @@
+use js_sys::{Float32Array, Uint32Array, Uint8Array};
+use num_complex::Complex32;
+use wasm_bindgen::prelude::*;
+
+// what is known about f at a vertex, stored as u8 in PlotResult's mask
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexKind {
+    // f is NaN there, the vertex is left out of the mesh
+    Undefined = 0,
+    Finite = 1,
+    // f (or the height) is infinite, the vertex sits at the edge of the
+    // height range
+    Pole = 2,
+}
+
+impl VertexKind {
+    // `height` is the plotted quantity of f. |f| is infinite as soon as one
+    // component is, even if the other is NaN, so that counts as a pole
+    pub fn of(f: Complex32, height: f32) -> VertexKind {
+        if f.norm().is_infinite() || height.is_infinite() {
+            VertexKind::Pole
+        } else if f.is_nan() || height.is_nan() {
+            VertexKind::Undefined
+        } else {
+            VertexKind::Finite
+        }
+    }
+}
+
+// everything the front-end needs to build a mesh for one evaluated grid,
+// each buffer kept separately so nothing has to be sliced apart in JS
+#[wasm_bindgen]
+pub struct PlotResult {
+    // (re, im, height) per vertex
+    positions: Vec<f32>,
+    // rgb or rgba per vertex, see color_channels
+    colors: Vec<f32>,
+    // VertexKind per vertex
+    mask: Vec<u8>,
+    // 2 triangles per grid cell, minus those touching an undefined vertex
+    indices: Vec<u32>,
+    // unit surface normal per vertex
+    normals: Vec<f32>,
+    // raw f(z) as (re, im) per vertex
+    values: Vec<f32>,
+    // number of vertices along the real axis
+    #[wasm_bindgen(readonly)]
+    pub cols: usize,
+    // number of vertices along the imaginary axis
+    #[wasm_bindgen(readonly)]
+    pub rows: usize,
+    #[wasm_bindgen(readonly, js_name = colorChannels)]
+    pub color_channels: usize,
+    #[wasm_bindgen(readonly, js_name = reMin)]
+    pub re_min: f32,
+    #[wasm_bindgen(readonly, js_name = reMax)]
+    pub re_max: f32,
+    #[wasm_bindgen(readonly, js_name = imMin)]
+    pub im_min: f32,
+    #[wasm_bindgen(readonly, js_name = imMax)]
+    pub im_max: f32,
+    // smallest and largest height of the finite vertices
+    #[wasm_bindgen(readonly, js_name = heightMin)]
+    pub height_min: f32,
+    #[wasm_bindgen(readonly, js_name = heightMax)]
+    pub height_max: f32,
+}
+
+impl PlotResult {
+    // vertices are laid out real-major: vertex (i, j) is at index i * rows + j
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        cols: usize,
+        rows: usize,
+        bounds: (f32, f32, f32, f32),
+        positions: Vec<f32>,
+        colors: Vec<f32>,
+        color_channels: usize,
+        values: Vec<f32>,
+        mask: &[VertexKind],
+    ) -> PlotResult {
+        let (re_min, re_max, im_min, im_max) = bounds;
+        let mut height_min = f32::INFINITY;
+        let mut height_max = f32::NEG_INFINITY;
+        for (h, kind) in positions.iter().skip(2).step_by(3).zip(mask) {
+            if *kind == VertexKind::Finite && h.is_finite() {
+                height_min = height_min.min(*h);
+                height_max = height_max.max(*h);
+            }
+        }
+        // nothing finite at all, pretend the surface is flat
+        if height_min > height_max {
+            height_min = 0.;
+            height_max = 0.;
+        }
+        let indices = grid_indices(cols, rows, mask);
+        let normals = grid_normals(&positions, cols, rows, mask);
+        PlotResult {
+            positions,
+            colors,
+            mask: mask.iter().map(|&kind| kind as u8).collect(),
+            indices,
+            normals,
+            values,
+            cols,
+            rows,
+            color_channels,
+            re_min,
+            re_max,
+            im_min,
+            im_max,
+            height_min,
+            height_max,
+        }
+    }
+
+    pub fn positions(&self) -> &[f32] {
+        &self.positions
+    }
+
+    pub fn colors(&self) -> &[f32] {
+        &self.colors
+    }
+
+    pub fn mask(&self) -> &[u8] {
+        &self.mask
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn normals(&self) -> &[f32] {
+        &self.normals
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.cols * self.rows
+    }
+}
+
+// the views below point straight into wasm memory instead of copying, so they
+// are only valid until wasm memory grows (i.e. until the next call into wasm
+// that allocates): upload or copy them before evaluating anything else
+#[wasm_bindgen]
+impl PlotResult {
+    #[wasm_bindgen(getter, js_name = positions)]
+    pub fn positions_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(&self.positions) }
+    }
+
+    #[wasm_bindgen(getter, js_name = colors)]
+    pub fn colors_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(&self.colors) }
+    }
+
+    // 0 undefined, 1 finite, 2 pole (see VertexKind)
+    #[wasm_bindgen(getter, js_name = mask)]
+    pub fn mask_view(&self) -> Uint8Array {
+        unsafe { Uint8Array::view(&self.mask) }
+    }
+
+    #[wasm_bindgen(getter, js_name = indices)]
+    pub fn indices_view(&self) -> Uint32Array {
+        unsafe { Uint32Array::view(&self.indices) }
+    }
+
+    #[wasm_bindgen(getter, js_name = normals)]
+    pub fn normals_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(&self.normals) }
+    }
+
+    #[wasm_bindgen(getter, js_name = values)]
+    pub fn values_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(&self.values) }
+    }
+
+    #[wasm_bindgen(getter, js_name = vertexCount)]
+    pub fn vertex_count_js(&self) -> usize {
+        self.vertex_count()
+    }
+}
+
+// undefined vertices have no meaningful position, so every triangle touching
+// one is dropped and the mesh gets a hole there
+fn grid_indices(cols: usize, rows: usize, mask: &[VertexKind]) -> Vec<u32> {
+    let defined = |k: u32| mask[k as usize] != VertexKind::Undefined;
+    let mut indices = Vec::new();
+    for i in 0..cols.saturating_sub(1) {
+        for j in 0..rows.saturating_sub(1) {
+            let a = (i * rows + j) as u32;
+            let b = ((i + 1) * rows + j) as u32;
+            let c = a + 1;
+            let d = b + 1;
+            // counter-clockwise seen from above
+            for tri in [[a, b, c], [b, d, c]] {
+                if tri.iter().all(|&k| defined(k)) {
+                    indices.extend_from_slice(&tri);
+                }
+            }
+        }
+    }
+    indices
+}
+
+// normals of the height field from central differences (one-sided at the edges
+// and next to undefined vertices)
+fn grid_normals(positions: &[f32], cols: usize, rows: usize, mask: &[VertexKind]) -> Vec<f32> {
+    let at = |i: usize, j: usize, k: usize| positions[3 * (i * rows + j) + k];
+    let defined = |i: usize, j: usize| mask[i * rows + j] != VertexKind::Undefined;
+    let slope = |a: (usize, usize), b: (usize, usize), axis: usize| {
+        let run = at(b.0, b.1, axis) - at(a.0, a.1, axis);
+        let rise = at(b.0, b.1, 2) - at(a.0, a.1, 2);
+        if run != 0. && rise.is_finite() {
+            rise / run
+        } else {
+            0.
+        }
+    };
+    let mut normals = Vec::with_capacity(3 * cols * rows);
+    for i in 0..cols {
+        for j in 0..rows {
+            let (mut i0, mut i1) = (i.saturating_sub(1), (i + 1).min(cols - 1));
+            let (mut j0, mut j1) = (j.saturating_sub(1), (j + 1).min(rows - 1));
+            if !defined(i0, j) {
+                i0 = i;
+            }
+            if !defined(i1, j) {
+                i1 = i;
+            }
+            if !defined(i, j0) {
+                j0 = j;
+            }
+            if !defined(i, j1) {
+                j1 = j;
+            }
+            let dx = slope((i0, j), (i1, j), 0);
+            let dy = slope((i, j0), (i, j1), 1);
+            let len = (dx * dx + dy * dy + 1.).sqrt();
+            if len.is_finite() {
+                normals.extend_from_slice(&[-dx / len, -dy / len, 1. / len]);
+            } else {
+                // too steep to tell
+                normals.extend_from_slice(&[0., 0., 1.]);
+            }
+        }
+    }
+    normals
+}
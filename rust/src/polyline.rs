@@ -0,0 +1,187 @@
+use js_sys::{Float32Array, Uint32Array};
+use wasm_bindgen::prelude::*;
+
+// a set of polylines in the coordinates of PlotResult's positions (re, im,
+// height), so they can share the mesh's transform in three.js
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Polylines {
+    // (x, y, z) per point, all lines one after the other
+    points: Vec<f32>,
+    // line k is points offsets[k] .. offsets[k + 1] (in points, not floats)
+    offsets: Vec<u32>,
+}
+
+impl Polylines {
+    pub fn new() -> Polylines {
+        Polylines {
+            points: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+
+    // lines with fewer than 2 points are dropped
+    pub fn push(&mut self, line: &[[f32; 3]]) {
+        if line.len() < 2 {
+            return;
+        }
+        for p in line {
+            self.points.extend_from_slice(p);
+        }
+        self.offsets.push((self.points.len() / 3) as u32);
+    }
+
+    pub fn append(&mut self, other: &Polylines) {
+        for line in other.lines() {
+            self.push(&line);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn line(&self, k: usize) -> Vec<[f32; 3]> {
+        let (from, to) = (self.offsets[k] as usize, self.offsets[k + 1] as usize);
+        self.points[3 * from..3 * to]
+            .chunks(3)
+            .map(|p| [p[0], p[1], p[2]])
+            .collect()
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Vec<[f32; 3]>> + '_ {
+        (0..self.len()).map(move |k| self.line(k))
+    }
+
+    pub fn points(&self) -> &[f32] {
+        &self.points
+    }
+
+    pub fn offsets(&self) -> &[u32] {
+        &self.offsets
+    }
+
+    // the lines as svg path data, one subpath each, in (x, -y) so the
+    // imaginary axis points up. the third coordinate is dropped
+    pub fn svg_path(&self) -> String {
+        let mut d = String::new();
+        for line in self.lines() {
+            for (k, p) in line.iter().enumerate() {
+                let cmd = if k == 0 { 'M' } else { 'L' };
+                d.push_str(&format!("{}{} {} ", cmd, p[0], -p[1]));
+            }
+        }
+        d.trim_end().to_string()
+    }
+
+    // (x, y, width, height) of the lines in svg coordinates (y flipped)
+    fn svg_bounds(&self) -> (f32, f32, f32, f32) {
+        let (mut lo, mut hi) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+        for p in self.points.chunks(3) {
+            for (axis, v) in [p[0], -p[1]].iter().enumerate() {
+                lo[axis] = lo[axis].min(*v);
+                hi[axis] = hi[axis].max(*v);
+            }
+        }
+        if lo[0] > hi[0] {
+            // nothing to draw, show the plotted square
+            return (-10., -10., 20., 20.);
+        }
+        let margin = 0.02 * (hi[0] - lo[0]).max(hi[1] - lo[1]).max(1e-3);
+        (
+            lo[0] - margin,
+            lo[1] - margin,
+            hi[0] - lo[0] + 2. * margin,
+            hi[1] - lo[1] + 2. * margin,
+        )
+    }
+}
+
+impl Default for Polylines {
+    fn default() -> Polylines {
+        Polylines::new()
+    }
+}
+
+// views into wasm memory like PlotResult's, copy them before calling into wasm
+// again
+#[wasm_bindgen]
+impl Polylines {
+    #[wasm_bindgen(getter, js_name = points)]
+    pub fn points_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(&self.points) }
+    }
+
+    #[wasm_bindgen(getter, js_name = offsets)]
+    pub fn offsets_view(&self) -> Uint32Array {
+        unsafe { Uint32Array::view(&self.offsets) }
+    }
+
+    #[wasm_bindgen(getter, js_name = lineCount)]
+    pub fn line_count(&self) -> usize {
+        self.len()
+    }
+
+    // a standalone svg of the lines fitted to their bounding box, 800 px on
+    // the longer side, stroked with the given colour and width in px
+    #[wasm_bindgen(js_name = toSvg)]
+    pub fn to_svg(&self, stroke: &str, stroke_width: f32) -> String {
+        let (x, y, w, h) = self.svg_bounds();
+        let scale = 800. / w.max(h);
+        format!(
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+                "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" ",
+                "stroke-linejoin=\"round\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "</svg>\n"
+            ),
+            (w * scale).round(),
+            (h * scale).round(),
+            x,
+            y,
+            w,
+            h,
+            self.svg_path(),
+            xml_escape(stroke),
+            stroke_width
+        )
+    }
+}
+
+// s with the characters that would end or break out of an xml attribute value
+// replaced by entities
+pub(crate) fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_svg_escapes_stroke() {
+        let mut lines = Polylines::new();
+        lines.push(&[[0., 0., 0.], [1., 1., 0.]]);
+        let svg = lines.to_svg("red\" onload=\"alert('x')\"><script>", 2.);
+        assert!(svg.contains(
+            "stroke=\"red&quot; onload=&quot;alert(&apos;x&apos;)&quot;&gt;&lt;script&gt;\""
+        ));
+        assert!(!svg.contains("<script>"));
+        assert!(lines.to_svg("#ff8800", 2.).contains("stroke=\"#ff8800\""));
+    }
+}
@@ -0,0 +1,1057 @@
+use num_complex::Complex32;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Sub};
+
+// number of f32 lanes in a vector register
+pub const WIDTH: usize = 4;
+
+// the backends: SSE2 on x86_64 and NEON on aarch64 (both part of the
+// baseline, so no runtime detection), simd128 on wasm32 when it is enabled
+// with -C target-feature=+simd128, and plain arrays everywhere else. only
+// lane-wise IEEE operations are used (no fused multiply-add), so every
+// backend gives the same bits
+#[cfg(target_arch = "x86_64")]
+mod imp {
+    use std::arch::x86_64::*;
+
+    pub type F = __m128;
+    pub type U = __m128i;
+
+    pub fn splat(x: f32) -> F {
+        unsafe { _mm_set1_ps(x) }
+    }
+    pub fn splat_u(x: u32) -> U {
+        unsafe { _mm_set1_epi32(x as i32) }
+    }
+    pub fn from_array(a: [f32; 4]) -> F {
+        unsafe { _mm_loadu_ps(a.as_ptr()) }
+    }
+    pub fn to_array(x: F) -> [f32; 4] {
+        let mut a = [0.; 4];
+        unsafe { _mm_storeu_ps(a.as_mut_ptr(), x) };
+        a
+    }
+    pub fn add(a: F, b: F) -> F {
+        unsafe { _mm_add_ps(a, b) }
+    }
+    pub fn sub(a: F, b: F) -> F {
+        unsafe { _mm_sub_ps(a, b) }
+    }
+    pub fn mul(a: F, b: F) -> F {
+        unsafe { _mm_mul_ps(a, b) }
+    }
+    pub fn div(a: F, b: F) -> F {
+        unsafe { _mm_div_ps(a, b) }
+    }
+    pub fn sqrt(a: F) -> F {
+        unsafe { _mm_sqrt_ps(a) }
+    }
+    pub fn lt(a: F, b: F) -> U {
+        unsafe { _mm_castps_si128(_mm_cmplt_ps(a, b)) }
+    }
+    pub fn le(a: F, b: F) -> U {
+        unsafe { _mm_castps_si128(_mm_cmple_ps(a, b)) }
+    }
+    pub fn eq(a: F, b: F) -> U {
+        unsafe { _mm_castps_si128(_mm_cmpeq_ps(a, b)) }
+    }
+    pub fn to_bits(a: F) -> U {
+        unsafe { _mm_castps_si128(a) }
+    }
+    pub fn from_bits(a: U) -> F {
+        unsafe { _mm_castsi128_ps(a) }
+    }
+    pub fn add_u(a: U, b: U) -> U {
+        unsafe { _mm_add_epi32(a, b) }
+    }
+    pub fn sub_u(a: U, b: U) -> U {
+        unsafe { _mm_sub_epi32(a, b) }
+    }
+    pub fn and(a: U, b: U) -> U {
+        unsafe { _mm_and_si128(a, b) }
+    }
+    pub fn or(a: U, b: U) -> U {
+        unsafe { _mm_or_si128(a, b) }
+    }
+    pub fn xor(a: U, b: U) -> U {
+        unsafe { _mm_xor_si128(a, b) }
+    }
+    pub fn shl<const N: i32>(a: U) -> U {
+        unsafe { _mm_slli_epi32::<N>(a) }
+    }
+    pub fn shr<const N: i32>(a: U) -> U {
+        unsafe { _mm_srli_epi32::<N>(a) }
+    }
+    pub fn sar<const N: i32>(a: U) -> U {
+        unsafe { _mm_srai_epi32::<N>(a) }
+    }
+    pub fn any(m: U) -> bool {
+        unsafe { _mm_movemask_ps(_mm_castsi128_ps(m)) != 0 }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod imp {
+    use std::arch::aarch64::*;
+
+    pub type F = float32x4_t;
+    pub type U = uint32x4_t;
+
+    pub fn splat(x: f32) -> F {
+        unsafe { vdupq_n_f32(x) }
+    }
+    pub fn splat_u(x: u32) -> U {
+        unsafe { vdupq_n_u32(x) }
+    }
+    pub fn from_array(a: [f32; 4]) -> F {
+        unsafe { vld1q_f32(a.as_ptr()) }
+    }
+    pub fn to_array(x: F) -> [f32; 4] {
+        let mut a = [0.; 4];
+        unsafe { vst1q_f32(a.as_mut_ptr(), x) };
+        a
+    }
+    pub fn add(a: F, b: F) -> F {
+        unsafe { vaddq_f32(a, b) }
+    }
+    pub fn sub(a: F, b: F) -> F {
+        unsafe { vsubq_f32(a, b) }
+    }
+    pub fn mul(a: F, b: F) -> F {
+        unsafe { vmulq_f32(a, b) }
+    }
+    pub fn div(a: F, b: F) -> F {
+        unsafe { vdivq_f32(a, b) }
+    }
+    pub fn sqrt(a: F) -> F {
+        unsafe { vsqrtq_f32(a) }
+    }
+    pub fn lt(a: F, b: F) -> U {
+        unsafe { vcltq_f32(a, b) }
+    }
+    pub fn le(a: F, b: F) -> U {
+        unsafe { vcleq_f32(a, b) }
+    }
+    pub fn eq(a: F, b: F) -> U {
+        unsafe { vceqq_f32(a, b) }
+    }
+    pub fn to_bits(a: F) -> U {
+        unsafe { vreinterpretq_u32_f32(a) }
+    }
+    pub fn from_bits(a: U) -> F {
+        unsafe { vreinterpretq_f32_u32(a) }
+    }
+    pub fn add_u(a: U, b: U) -> U {
+        unsafe { vaddq_u32(a, b) }
+    }
+    pub fn sub_u(a: U, b: U) -> U {
+        unsafe { vsubq_u32(a, b) }
+    }
+    pub fn and(a: U, b: U) -> U {
+        unsafe { vandq_u32(a, b) }
+    }
+    pub fn or(a: U, b: U) -> U {
+        unsafe { vorrq_u32(a, b) }
+    }
+    pub fn xor(a: U, b: U) -> U {
+        unsafe { veorq_u32(a, b) }
+    }
+    pub fn shl<const N: i32>(a: U) -> U {
+        unsafe { vshlq_n_u32::<N>(a) }
+    }
+    pub fn shr<const N: i32>(a: U) -> U {
+        unsafe { vshrq_n_u32::<N>(a) }
+    }
+    pub fn sar<const N: i32>(a: U) -> U {
+        unsafe { vreinterpretq_u32_s32(vshrq_n_s32::<N>(vreinterpretq_s32_u32(a))) }
+    }
+    pub fn any(m: U) -> bool {
+        unsafe { vmaxvq_u32(m) != 0 }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod imp {
+    use std::arch::wasm32::*;
+
+    pub type F = v128;
+    pub type U = v128;
+
+    pub fn splat(x: f32) -> F {
+        f32x4_splat(x)
+    }
+    pub fn splat_u(x: u32) -> U {
+        u32x4_splat(x)
+    }
+    pub fn from_array(a: [f32; 4]) -> F {
+        f32x4(a[0], a[1], a[2], a[3])
+    }
+    pub fn to_array(x: F) -> [f32; 4] {
+        [
+            f32x4_extract_lane::<0>(x),
+            f32x4_extract_lane::<1>(x),
+            f32x4_extract_lane::<2>(x),
+            f32x4_extract_lane::<3>(x),
+        ]
+    }
+    pub fn add(a: F, b: F) -> F {
+        f32x4_add(a, b)
+    }
+    pub fn sub(a: F, b: F) -> F {
+        f32x4_sub(a, b)
+    }
+    pub fn mul(a: F, b: F) -> F {
+        f32x4_mul(a, b)
+    }
+    pub fn div(a: F, b: F) -> F {
+        f32x4_div(a, b)
+    }
+    pub fn sqrt(a: F) -> F {
+        f32x4_sqrt(a)
+    }
+    pub fn lt(a: F, b: F) -> U {
+        f32x4_lt(a, b)
+    }
+    pub fn le(a: F, b: F) -> U {
+        f32x4_le(a, b)
+    }
+    pub fn eq(a: F, b: F) -> U {
+        f32x4_eq(a, b)
+    }
+    pub fn to_bits(a: F) -> U {
+        a
+    }
+    pub fn from_bits(a: U) -> F {
+        a
+    }
+    pub fn add_u(a: U, b: U) -> U {
+        i32x4_add(a, b)
+    }
+    pub fn sub_u(a: U, b: U) -> U {
+        i32x4_sub(a, b)
+    }
+    pub fn and(a: U, b: U) -> U {
+        v128_and(a, b)
+    }
+    pub fn or(a: U, b: U) -> U {
+        v128_or(a, b)
+    }
+    pub fn xor(a: U, b: U) -> U {
+        v128_xor(a, b)
+    }
+    pub fn shl<const N: i32>(a: U) -> U {
+        i32x4_shl(a, N as u32)
+    }
+    pub fn shr<const N: i32>(a: U) -> U {
+        u32x4_shr(a, N as u32)
+    }
+    pub fn sar<const N: i32>(a: U) -> U {
+        i32x4_shr(a, N as u32)
+    }
+    pub fn any(m: U) -> bool {
+        v128_any_true(m)
+    }
+}
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    all(target_arch = "wasm32", target_feature = "simd128")
+)))]
+mod imp {
+    pub type F = [f32; 4];
+    pub type U = [u32; 4];
+
+    fn map<T: Copy, R>(a: [T; 4], f: impl Fn(T) -> R) -> [R; 4] {
+        [f(a[0]), f(a[1]), f(a[2]), f(a[3])]
+    }
+    fn zip<T: Copy, R>(a: [T; 4], b: [T; 4], f: impl Fn(T, T) -> R) -> [R; 4] {
+        [f(a[0], b[0]), f(a[1], b[1]), f(a[2], b[2]), f(a[3], b[3])]
+    }
+    fn mask(m: bool) -> u32 {
+        if m {
+            !0
+        } else {
+            0
+        }
+    }
+
+    pub fn splat(x: f32) -> F {
+        [x; 4]
+    }
+    pub fn splat_u(x: u32) -> U {
+        [x; 4]
+    }
+    pub fn from_array(a: [f32; 4]) -> F {
+        a
+    }
+    pub fn to_array(x: F) -> [f32; 4] {
+        x
+    }
+    pub fn add(a: F, b: F) -> F {
+        zip(a, b, |a, b| a + b)
+    }
+    pub fn sub(a: F, b: F) -> F {
+        zip(a, b, |a, b| a - b)
+    }
+    pub fn mul(a: F, b: F) -> F {
+        zip(a, b, |a, b| a * b)
+    }
+    pub fn div(a: F, b: F) -> F {
+        zip(a, b, |a, b| a / b)
+    }
+    pub fn sqrt(a: F) -> F {
+        map(a, f32::sqrt)
+    }
+    pub fn lt(a: F, b: F) -> U {
+        zip(a, b, |a, b| mask(a < b))
+    }
+    pub fn le(a: F, b: F) -> U {
+        zip(a, b, |a, b| mask(a <= b))
+    }
+    pub fn eq(a: F, b: F) -> U {
+        zip(a, b, |a, b| mask(a == b))
+    }
+    pub fn to_bits(a: F) -> U {
+        map(a, f32::to_bits)
+    }
+    pub fn from_bits(a: U) -> F {
+        map(a, f32::from_bits)
+    }
+    pub fn add_u(a: U, b: U) -> U {
+        zip(a, b, u32::wrapping_add)
+    }
+    pub fn sub_u(a: U, b: U) -> U {
+        zip(a, b, u32::wrapping_sub)
+    }
+    pub fn and(a: U, b: U) -> U {
+        zip(a, b, |a, b| a & b)
+    }
+    pub fn or(a: U, b: U) -> U {
+        zip(a, b, |a, b| a | b)
+    }
+    pub fn xor(a: U, b: U) -> U {
+        zip(a, b, |a, b| a ^ b)
+    }
+    pub fn shl<const N: i32>(a: U) -> U {
+        map(a, |a| a << N)
+    }
+    pub fn shr<const N: i32>(a: U) -> U {
+        map(a, |a| a >> N)
+    }
+    pub fn sar<const N: i32>(a: U) -> U {
+        map(a, |a| ((a as i32) >> N) as u32)
+    }
+    pub fn any(m: U) -> bool {
+        m.iter().any(|&m| m != 0)
+    }
+}
+
+// WIDTH f32 lanes
+#[derive(Clone, Copy)]
+pub struct F32x4(imp::F);
+
+// WIDTH u32 lanes, for bit manipulation and as the masks comparisons return
+// (all ones where true)
+#[derive(Clone, Copy)]
+pub struct U32x4(imp::U);
+
+impl F32x4 {
+    pub fn splat(x: f32) -> F32x4 {
+        F32x4(imp::splat(x))
+    }
+
+    pub fn new(a: [f32; WIDTH]) -> F32x4 {
+        F32x4(imp::from_array(a))
+    }
+
+    pub fn to_array(self) -> [f32; WIDTH] {
+        imp::to_array(self.0)
+    }
+
+    pub fn to_bits(self) -> U32x4 {
+        U32x4(imp::to_bits(self.0))
+    }
+
+    pub fn from_bits(bits: U32x4) -> F32x4 {
+        F32x4(imp::from_bits(bits.0))
+    }
+
+    pub fn sqrt(self) -> F32x4 {
+        F32x4(imp::sqrt(self.0))
+    }
+
+    pub fn abs(self) -> F32x4 {
+        F32x4::from_bits(self.to_bits() & U32x4::splat(0x7fff_ffff))
+    }
+
+    // the sign bit of every lane as a mask
+    pub fn is_sign_negative(self) -> U32x4 {
+        self.to_bits().sar::<31>()
+    }
+
+    // flips the sign where the mask (or just its sign bit) is set
+    pub fn flip_sign(self, mask: U32x4) -> F32x4 {
+        F32x4::from_bits(self.to_bits() ^ (mask & U32x4::splat(0x8000_0000)))
+    }
+
+    pub fn lt(self, o: F32x4) -> U32x4 {
+        U32x4(imp::lt(self.0, o.0))
+    }
+
+    pub fn le(self, o: F32x4) -> U32x4 {
+        U32x4(imp::le(self.0, o.0))
+    }
+
+    pub fn gt(self, o: F32x4) -> U32x4 {
+        o.lt(self)
+    }
+
+    pub fn ge(self, o: F32x4) -> U32x4 {
+        o.le(self)
+    }
+
+    pub fn eq(self, o: F32x4) -> U32x4 {
+        U32x4(imp::eq(self.0, o.0))
+    }
+
+    pub fn is_nan(self) -> U32x4 {
+        !self.eq(self)
+    }
+}
+
+impl U32x4 {
+    pub fn splat(x: u32) -> U32x4 {
+        U32x4(imp::splat_u(x))
+    }
+
+    pub fn shl<const N: i32>(self) -> U32x4 {
+        U32x4(imp::shl::<N>(self.0))
+    }
+
+    // logical shift right
+    pub fn shr<const N: i32>(self) -> U32x4 {
+        U32x4(imp::shr::<N>(self.0))
+    }
+
+    // arithmetic shift right
+    pub fn sar<const N: i32>(self) -> U32x4 {
+        U32x4(imp::sar::<N>(self.0))
+    }
+
+    // a where the mask is set, b elsewhere
+    pub fn select(self, a: F32x4, b: F32x4) -> F32x4 {
+        F32x4::from_bits(self.select_bits(a.to_bits(), b.to_bits()))
+    }
+
+    pub fn select_bits(self, a: U32x4, b: U32x4) -> U32x4 {
+        b ^ ((a ^ b) & self)
+    }
+
+    // whether any lane of the mask is set
+    pub fn any(self) -> bool {
+        imp::any(self.0)
+    }
+
+    // the mask lane by lane (a set lane has all bits set, which is a NaN)
+    pub fn to_array(self) -> [bool; WIDTH] {
+        F32x4::from_bits(self).to_array().map(f32::is_nan)
+    }
+}
+
+macro_rules! binop {
+    ($t:ty, $trait:ident, $method:ident, $f:path) => {
+        impl $trait for $t {
+            type Output = $t;
+            fn $method(self, o: $t) -> $t {
+                Self($f(self.0, o.0))
+            }
+        }
+    };
+}
+
+binop!(F32x4, Add, add, imp::add);
+binop!(F32x4, Sub, sub, imp::sub);
+binop!(F32x4, Mul, mul, imp::mul);
+binop!(F32x4, Div, div, imp::div);
+binop!(U32x4, Add, add, imp::add_u);
+binop!(U32x4, Sub, sub, imp::sub_u);
+binop!(U32x4, BitAnd, bitand, imp::and);
+binop!(U32x4, BitOr, bitor, imp::or);
+binop!(U32x4, BitXor, bitxor, imp::xor);
+
+impl Neg for F32x4 {
+    type Output = F32x4;
+    fn neg(self) -> F32x4 {
+        self.flip_sign(U32x4::splat(!0))
+    }
+}
+
+impl std::ops::Not for U32x4 {
+    type Output = U32x4;
+    fn not(self) -> U32x4 {
+        self ^ U32x4::splat(!0)
+    }
+}
+
+// --------------------------------------------------------------
+// elementary functions
+// --------------------------------------------------------------
+// after Cephes' single precision expf, logf, sinf/cosf and atanf: about 1 ulp
+// for exp, ln and sin/cos (|x| <= 8192), 2 ulp for sinh/cosh, atan2 and hypot
+
+fn splat(x: f32) -> F32x4 {
+    F32x4::splat(x)
+}
+
+// Horner's rule, highest coefficient first
+fn poly(x: F32x4, coeffs: &[f32]) -> F32x4 {
+    let mut acc = splat(coeffs[0]);
+    for &c in &coeffs[1..] {
+        acc = acc * x + splat(c);
+    }
+    acc
+}
+
+// adding and subtracting 1.5 * 2^23 rounds |x| < 2^22 to an integer (ties to
+// even), and leaves that integer in the low bits of the sum
+const MAGIC: f32 = 12582912.;
+
+fn round(x: F32x4) -> F32x4 {
+    (x + splat(MAGIC)) - splat(MAGIC)
+}
+
+// an integral x with |x| < 2^22 as an i32 (in two's complement)
+fn to_int(x: F32x4) -> U32x4 {
+    (x + splat(MAGIC)).to_bits() - splat(MAGIC).to_bits()
+}
+
+fn to_float(k: U32x4) -> F32x4 {
+    F32x4::from_bits(k + splat(MAGIC).to_bits()) - splat(MAGIC)
+}
+
+// 2^k for -126 <= k <= 127
+fn pow2(k: U32x4) -> F32x4 {
+    F32x4::from_bits((k + U32x4::splat(127)).shl::<23>())
+}
+
+// ln 2 split so k * LN2_HI is exact for |k| < 2^15
+const LN2_HI: f32 = 0.693359375;
+const LN2_LO: f32 = -2.12194440e-4;
+
+pub fn exp(x: F32x4) -> F32x4 {
+    // x = k ln 2 + r with |r| <= ln 2 / 2
+    let k = round(x * splat(std::f32::consts::LOG2_E));
+    let r = (x - k * splat(LN2_HI)) - k * splat(LN2_LO);
+    let z = r * r;
+    let p = poly(
+        r,
+        &[
+            1.9875691500e-4,
+            1.3981999507e-3,
+            8.3334519073e-3,
+            4.1665795894e-2,
+            1.6666665459e-1,
+            5.0000001201e-1,
+        ],
+    );
+    let y = p * z + r + splat(1.);
+    // 2^k in two halves, so neither over- nor underflows while the result
+    // is still finite and non-zero
+    let k = to_int(k);
+    let half = k.sar::<1>();
+    let y = y * pow2(half) * pow2(k - half);
+    // beyond these k (and the halves) get out of range, e^x is inf or 0
+    let y = x.gt(splat(100.)).select(splat(f32::INFINITY), y);
+    let y = x.lt(splat(-120.)).select(splat(0.), y);
+    x.is_nan().select(x, y)
+}
+
+pub fn ln(x: F32x4) -> F32x4 {
+    // x = 2^e m with m in [sqrt(1/2), sqrt(2)), subnormals scaled up first
+    let tiny = x.lt(splat(f32::MIN_POSITIVE));
+    let scaled = tiny.select(x * splat(33554432.), x);
+    let bits = scaled.to_bits();
+    let e =
+        bits.shr::<23>() - U32x4::splat(126) - tiny.select_bits(U32x4::splat(25), U32x4::splat(0));
+    let m = F32x4::from_bits((bits & U32x4::splat(0x007f_ffff)) | U32x4::splat(0x3f00_0000));
+    let low = m.lt(splat(std::f32::consts::FRAC_1_SQRT_2));
+    let e = to_float(e - low.select_bits(U32x4::splat(1), U32x4::splat(0)));
+    let f = low.select(m + m, m) - splat(1.);
+    // ln(1 + f)
+    let z = f * f;
+    let y = poly(
+        f,
+        &[
+            7.0376836292e-2,
+            -1.1514610310e-1,
+            1.1676998740e-1,
+            -1.2420140846e-1,
+            1.4249322787e-1,
+            -1.6668057665e-1,
+            2.0000714765e-1,
+            -2.4999993993e-1,
+            3.3333331174e-1,
+        ],
+    ) * f
+        * z;
+    let y = y + e * splat(LN2_LO) - z * splat(0.5);
+    let y = f + y + e * splat(LN2_HI);
+    let y = x.eq(splat(0.)).select(splat(f32::NEG_INFINITY), y);
+    let y = x.eq(splat(f32::INFINITY)).select(x, y);
+    // NaN and negative x
+    x.ge(splat(0.)).select(y, splat(f32::NAN))
+}
+
+const DP: [f32; 6] = [
+    0.78515625,
+    2.4175643920898438e-4,
+    1.5692785382270813e-7,
+    3.035438567167148e-11,
+    3.108624468950438e-14,
+    3.0616171314629196e-17,
+];
+
+// (sin x, cos x). the reduction by multiples of π/4 is only accurate for
+// |x| <= 8192, lanes beyond that (or not finite) use f32::sin and f32::cos
+pub fn sin_cos(x: F32x4) -> (F32x4, F32x4) {
+    let a = x.abs();
+    // the octant j = floor(4a/π), rounded up to even: a = jπ/4 + r with
+    // |r| <= π/4 and j = 2q for the quadrant q
+    let t = a * splat(std::f32::consts::FRAC_2_PI * 2.);
+    let n = round(t);
+    let j = t.lt(n).select(n - splat(1.), n);
+    let odd = to_int(j).shl::<31>().sar::<31>();
+    let j = odd.select(j + splat(1.), j);
+    // π/4 split into 10 bit pieces so that j DP is exact for j < 2^14, and
+    // a last full one: the nearest f32 to a multiple of π/4 below 8192 is
+    // still 2e-9 away, so r needs about 70 bits of π. the rounding error of
+    // each subtraction is carried along so r is only rounded once
+    let (r, err) = DP.iter().fold((a, splat(0.)), |(r, err), &dp| {
+        let p = -(j * splat(dp));
+        let s = r + p;
+        let b = s - r;
+        (s, err + ((r - (s - b)) + (p - b)))
+    });
+    let r = r + err;
+    let z = r * r;
+    let s = poly(z, &[-1.9515295891e-4, 8.3321608736e-3, -1.6666654611e-1]) * z * r + r;
+    let c = poly(
+        z,
+        &[
+            2.443315711809948e-5,
+            -1.388731625493765e-3,
+            4.166664568298827e-2,
+        ],
+    ) * z
+        * z
+        - z * splat(0.5)
+        + splat(1.);
+    let q = to_int(j).shr::<1>();
+    let swap = q.shl::<31>().sar::<31>();
+    let sin = swap.select(c, s).flip_sign(q.shl::<30>() ^ x.to_bits());
+    let cos = swap
+        .select(s, c)
+        .flip_sign((q + U32x4::splat(1)).shl::<30>());
+    let wide = !a.le(splat(8192.));
+    if !wide.any() {
+        return (sin, cos);
+    }
+    let (mut sin, mut cos) = (sin.to_array(), cos.to_array());
+    let x = x.to_array();
+    for (k, &wide) in wide.to_array().iter().enumerate() {
+        if wide {
+            sin[k] = x[k].sin();
+            cos[k] = x[k].cos();
+        }
+    }
+    (F32x4::new(sin), F32x4::new(cos))
+}
+
+// (sinh x, cosh x)
+pub fn sinh_cosh(x: F32x4) -> (F32x4, F32x4) {
+    let a = x.abs();
+    let e = exp(a);
+    let half = splat(0.5);
+    let inv = half / e;
+    let cosh = half * e + inv;
+    let sinh = half * e - inv;
+    // below 1, where e - 1/e cancels, the Taylor series
+    let z = a * a;
+    let series = poly(
+        z,
+        &[
+            1. / 6227020800.,
+            1. / 39916800.,
+            1. / 362880.,
+            1. / 5040.,
+            1. / 120.,
+            1. / 6.,
+        ],
+    ) * z
+        * a
+        + a;
+    let sinh = a.lt(splat(1.)).select(series, sinh);
+    // past 9 1/e doesn't matter, and e would overflow before e/2 does:
+    // e^a / 2 = e^(a - LN2_HI) e^LN2_HI / 2, where a - LN2_HI is exact
+    let big = a.gt(splat(9.));
+    let e2 = exp(a - splat(LN2_HI)) * splat(1.0002121925);
+    let cosh = big.select(e2, cosh);
+    let sinh = big.select(e2, sinh);
+    (sinh.flip_sign(x.to_bits()), cosh)
+}
+
+// the angle of (x, y), in (-π, π]
+pub fn atan2(y: F32x4, x: F32x4) -> F32x4 {
+    let (ay, ax) = (y.abs(), x.abs());
+    let t = ay / ax;
+    // atan t = atan(t0) + atan((t - t0) / (1 + t t0)) for t0 = 0, 1 or ∞
+    let big = t.gt(splat(2.414213562373095));
+    let mid = t.gt(splat(0.4142135623730950)) & !big;
+    let u = big.select(
+        -(splat(1.) / t),
+        mid.select((t - splat(1.)) / (t + splat(1.)), t),
+    );
+    let offset = big.select(splat(FRAC_PI_2), mid.select(splat(FRAC_PI_4), splat(0.)));
+    let z = u * u;
+    let a = poly(
+        z,
+        &[
+            8.05374449538e-2,
+            -1.38776856032e-1,
+            1.99777106478e-1,
+            -3.33329491539e-1,
+        ],
+    ) * z
+        * u
+        + u
+        + offset;
+    // 0 / 0 and ∞ / ∞
+    let a = ay.eq(splat(0.)).select(splat(0.), a);
+    let inf = splat(f32::INFINITY);
+    let a = (ay.eq(inf) & ax.eq(inf)).select(splat(FRAC_PI_4), a);
+    // the left half plane, including x = -0
+    let a = x.is_sign_negative().select(splat(PI) - a, a);
+    let a = a.flip_sign(y.to_bits());
+    (x.is_nan() | y.is_nan()).select(splat(f32::NAN), a)
+}
+
+// sqrt(x² + y²) without overflow or underflow in the squares
+pub fn hypot(x: F32x4, y: F32x4) -> F32x4 {
+    let (ax, ay) = (x.abs(), y.abs());
+    let m = ax.gt(ay).select(ax, ay);
+    let huge = m.gt(splat(1.1529215e18));
+    let tiny = m.lt(splat(8.6736174e-19));
+    let scale = huge.select(
+        splat(8.4703295e-22),
+        tiny.select(splat(1.2379401e27), splat(1.)),
+    );
+    let unscale = huge.select(
+        splat(1.1805916e21),
+        tiny.select(splat(8.077936e-28), splat(1.)),
+    );
+    let (sx, sy) = (ax * scale, ay * scale);
+    let h = (sx * sx + sy * sy).sqrt() * unscale;
+    let inf = splat(f32::INFINITY);
+    (ax.eq(inf) | ay.eq(inf)).select(inf, h)
+}
+
+// --------------------------------------------------------------
+// complex lanes
+// --------------------------------------------------------------
+// WIDTH complex numbers in structure-of-arrays form. the formulas are
+// num_complex's, on the kernels above
+#[derive(Clone, Copy)]
+pub struct C32x4 {
+    pub re: F32x4,
+    pub im: F32x4,
+}
+
+impl C32x4 {
+    pub fn splat(c: Complex32) -> C32x4 {
+        C32x4 {
+            re: splat(c.re),
+            im: splat(c.im),
+        }
+    }
+
+    // the first WIDTH of zs
+    pub fn load(zs: &[Complex32]) -> C32x4 {
+        let (mut re, mut im) = ([0.; WIDTH], [0.; WIDTH]);
+        for (k, z) in zs[..WIDTH].iter().enumerate() {
+            re[k] = z.re;
+            im[k] = z.im;
+        }
+        C32x4 {
+            re: F32x4::new(re),
+            im: F32x4::new(im),
+        }
+    }
+
+    pub fn to_array(self) -> [Complex32; WIDTH] {
+        let (re, im) = (self.re.to_array(), self.im.to_array());
+        [0, 1, 2, 3].map(|k| Complex32::new(re[k], im[k]))
+    }
+
+    pub fn add(self, o: C32x4) -> C32x4 {
+        C32x4 {
+            re: self.re + o.re,
+            im: self.im + o.im,
+        }
+    }
+
+    pub fn sub(self, o: C32x4) -> C32x4 {
+        C32x4 {
+            re: self.re - o.re,
+            im: self.im - o.im,
+        }
+    }
+
+    pub fn mul(self, o: C32x4) -> C32x4 {
+        C32x4 {
+            re: self.re * o.re - self.im * o.im,
+            im: self.re * o.im + self.im * o.re,
+        }
+    }
+
+    // the plain quotient, and a mask of the lanes where it isn't normal
+    // (see MathContext's "/")
+    pub fn div(self, o: C32x4) -> (C32x4, U32x4) {
+        let norm_sqr = o.re * o.re + o.im * o.im;
+        let re = self.re * o.re + self.im * o.im;
+        let im = self.im * o.re - self.re * o.im;
+        let q = C32x4 {
+            re: re / norm_sqr,
+            im: im / norm_sqr,
+        };
+        let normal = |x: F32x4| {
+            let a = x.abs();
+            a.ge(splat(f32::MIN_POSITIVE)) & a.lt(splat(f32::INFINITY))
+        };
+        (q, !(normal(q.re) & normal(q.im)))
+    }
+
+    pub fn neg(self) -> C32x4 {
+        C32x4 {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+
+    pub fn conj(self) -> C32x4 {
+        C32x4 {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+
+    pub fn sin(self) -> C32x4 {
+        let (s, c) = sin_cos(self.re);
+        let (sh, ch) = sinh_cosh(self.im);
+        C32x4 {
+            re: s * ch,
+            im: c * sh,
+        }
+    }
+
+    pub fn cos(self) -> C32x4 {
+        let (s, c) = sin_cos(self.re);
+        let (sh, ch) = sinh_cosh(self.im);
+        C32x4 {
+            re: c * ch,
+            im: -s * sh,
+        }
+    }
+
+    pub fn sinh(self) -> C32x4 {
+        let (sh, ch) = sinh_cosh(self.re);
+        let (s, c) = sin_cos(self.im);
+        C32x4 {
+            re: sh * c,
+            im: ch * s,
+        }
+    }
+
+    pub fn cosh(self) -> C32x4 {
+        let (sh, ch) = sinh_cosh(self.re);
+        let (s, c) = sin_cos(self.im);
+        C32x4 {
+            re: ch * c,
+            im: sh * s,
+        }
+    }
+
+    pub fn exp(self) -> C32x4 {
+        let (re, im) = (self.re, self.im);
+        let r = exp(re);
+        let (s, c) = sin_cos(im);
+        let w = C32x4 {
+            re: r * c,
+            im: r * s,
+        };
+        // num_complex's corner cases: e^(+∞ + 0i) = +∞ and e^(+∞ + yi) is
+        // +∞ + NaN i for y infinite or NaN, e^(-∞ + yi) = 0 for those y and
+        // e^(NaN + 0i) = NaN
+        let inf = splat(f32::INFINITY);
+        let zero = splat(0.);
+        let finite = im.abs().lt(inf);
+        let pos_inf = re.eq(inf) & (im.eq(zero) | !finite);
+        let neg_inf = re.eq(-inf) & !finite;
+        let nan_real = re.is_nan() & im.eq(zero);
+        let keep = pos_inf | nan_real;
+        C32x4 {
+            re: keep.select(re, neg_inf.select(zero, w.re)),
+            im: keep.select(
+                im.abs().eq(inf).select(splat(f32::NAN), im),
+                neg_inf.select(zero, w.im),
+            ),
+        }
+    }
+
+    pub fn ln(self) -> C32x4 {
+        C32x4 {
+            re: ln(hypot(self.re, self.im)),
+            im: atan2(self.im, self.re),
+        }
+    }
+
+    pub fn norm(self) -> C32x4 {
+        C32x4 {
+            re: hypot(self.re, self.im),
+            im: splat(0.),
+        }
+    }
+
+    pub fn arg(self) -> C32x4 {
+        C32x4 {
+            re: atan2(self.im, self.re),
+            im: splat(0.),
+        }
+    }
+}
+
+// f at a single z. MathContext::eval goes through this for the functions
+// eval_many runs on whole vectors, so the two agree bit for bit
+pub fn one_lane(z: Complex32, f: fn(C32x4) -> C32x4) -> Complex32 {
+    f(C32x4::splat(z)).to_array()[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // |x - want| in units in the last place of want as an f32
+    fn ulps(x: f32, want: f64) -> f64 {
+        if x as f64 == want || (x.is_nan() && want.is_nan()) {
+            return 0.;
+        }
+        let w = want as f32;
+        if !w.is_finite() || !x.is_finite() {
+            return f64::INFINITY;
+        }
+        let ulp = if w.abs() < f32::MIN_POSITIVE {
+            f32::from_bits(1) as f64
+        } else {
+            (f32::from_bits(w.abs().to_bits() + 1) - w.abs()) as f64
+        };
+        (x as f64 - want).abs() / ulp
+    }
+
+    fn lanes(xs: &[f32], f: impl Fn(F32x4) -> F32x4) -> Vec<f32> {
+        xs.chunks(WIDTH)
+            .flat_map(|c| {
+                let mut a = [0.; WIDTH];
+                a[..c.len()].copy_from_slice(c);
+                f(F32x4::new(a)).to_array()[..c.len()].to_vec()
+            })
+            .collect()
+    }
+
+    fn worst(xs: &[f32], f: impl Fn(F32x4) -> F32x4, want: impl Fn(f64) -> f64) -> f64 {
+        let ys = lanes(xs, f);
+        xs.iter()
+            .zip(ys)
+            .map(|(&x, y)| ulps(y, want(x as f64)))
+            .fold(0., f64::max)
+    }
+
+    fn range(lo: f32, hi: f32, count: usize) -> Vec<f32> {
+        (0..=count)
+            .map(|k| lo + (hi - lo) * (k as f32 / count as f32))
+            .collect()
+    }
+
+    #[test]
+    fn accuracy() {
+        let xs = range(-103., 88.7, 200000);
+        assert!(worst(&xs, exp, f64::exp) <= 1.5);
+        // every exponent, mantissas spread out, including subnormals
+        let pos: Vec<f32> = (1..0x7f80_0000u32)
+            .step_by(9973)
+            .map(f32::from_bits)
+            .collect();
+        assert!(worst(&pos, ln, f64::ln) <= 1.);
+        let xs = range(-8192., 8192., 400000);
+        assert!(worst(&xs, |x| sin_cos(x).0, f64::sin) <= 1.5);
+        assert!(worst(&xs, |x| sin_cos(x).1, f64::cos) <= 1.5);
+        // the floats closest to a multiple of π/4, where r cancels
+        let near: Vec<f32> = (1..10430)
+            .flat_map(|j| {
+                let x = (j as f64 * std::f64::consts::FRAC_PI_4) as f32;
+                [x, f32::from_bits(x.to_bits() + 1), -x]
+            })
+            .collect();
+        assert!(worst(&near, |x| sin_cos(x).0, f64::sin) <= 1.5);
+        assert!(worst(&near, |x| sin_cos(x).1, f64::cos) <= 1.5);
+        let xs = range(-89.4, 89.4, 200000);
+        assert!(worst(&xs, |x| sinh_cosh(x).0, f64::sinh) <= 2.5);
+        assert!(worst(&xs, |x| sinh_cosh(x).1, f64::cosh) <= 2.5);
+        let small = range(-1.5, 1.5, 100000);
+        assert!(worst(&small, |x| sinh_cosh(x).0, f64::sinh) <= 2.5);
+        let ys = range(-5., 5., 1000);
+        for &x in &[-3e30, -2., -0.3, 1e-30, 0.7, 4., 1e25] {
+            let xv = splat(x);
+            let w = worst(&ys, |y| atan2(y, xv), |y| y.atan2(x as f64));
+            assert!(w <= 2.5, "atan2 at x = {}: {}", x, w);
+            let w = worst(&ys, |y| hypot(xv, y), |y| (x as f64).hypot(y));
+            assert!(w <= 1.5, "hypot at x = {}: {}", x, w);
+        }
+    }
+
+    #[test]
+    fn special_values() {
+        let inf = f32::INFINITY;
+        let bits = |x: F32x4| x.to_array().map(f32::to_bits);
+        let xs = F32x4::new([inf, -inf, f32::NAN, -0.]);
+        let e = exp(xs).to_array();
+        assert_eq!((e[0], e[1], e[3]), (inf, 0., 1.));
+        assert!(e[2].is_nan());
+        let l = ln(F32x4::new([0., inf, -1., 1.])).to_array();
+        assert_eq!((l[0], l[1], l[3]), (-inf, inf, 0.));
+        assert!(l[2].is_nan());
+        let (s, c) = sin_cos(F32x4::new([-0., 1e10, inf, 3.]));
+        assert_eq!(bits(s)[0], (-0f32).to_bits());
+        assert_eq!(s.to_array()[1], 1e10f32.sin());
+        assert_eq!(c.to_array()[1], 1e10f32.cos());
+        assert!(s.to_array()[2].is_nan() && c.to_array()[2].is_nan());
+        let (sh, ch) = sinh_cosh(F32x4::new([-0., 89.5, -inf, 1e-20]));
+        assert_eq!(bits(sh)[0], (-0f32).to_bits());
+        assert_eq!((sh.to_array()[1], ch.to_array()[1]), (inf, inf));
+        assert_eq!((sh.to_array()[2], ch.to_array()[2]), (-inf, inf));
+        assert_eq!(sh.to_array()[3], 1e-20);
+        // atan2's signed zeros and infinities
+        let y = F32x4::new([0., -0., 0., -0.]);
+        let x = F32x4::new([0., 0., -0., -1.]);
+        assert_eq!(bits(atan2(y, x)), [0., -0., PI, -PI].map(f32::to_bits));
+        let y = F32x4::new([inf, -inf, 1., f32::NAN]);
+        let x = F32x4::new([inf, -inf, -inf, 0.]);
+        let a = atan2(y, x).to_array();
+        assert_eq!(&a[..3], &[FRAC_PI_4, -3. * FRAC_PI_4, PI]);
+        assert!(a[3].is_nan());
+        let h = hypot(
+            F32x4::new([3e38, 1e-45, f32::NAN, 3.]),
+            F32x4::new([3e38, 1e-45, -inf, 4.]),
+        );
+        assert_eq!(h.to_array(), [inf, 1e-45, inf, 5.]);
+    }
+}
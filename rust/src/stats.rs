@@ -0,0 +1,124 @@
+use num_complex::Complex32;
+use wasm_bindgen::prelude::*;
+
+// summary of one quantity over the finite samples of a grid
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    // number of finite values the summary is over
+    #[wasm_bindgen(readonly)]
+    pub count: usize,
+    #[wasm_bindgen(readonly)]
+    pub min: f32,
+    #[wasm_bindgen(readonly)]
+    pub max: f32,
+    #[wasm_bindgen(readonly)]
+    pub mean: f32,
+    #[wasm_bindgen(readonly)]
+    pub p1: f32,
+    #[wasm_bindgen(readonly)]
+    pub p5: f32,
+    #[wasm_bindgen(readonly)]
+    pub median: f32,
+    #[wasm_bindgen(readonly)]
+    pub p95: f32,
+    #[wasm_bindgen(readonly)]
+    pub p99: f32,
+}
+
+impl Summary {
+    // non-finite values are skipped, everything is 0 if nothing is left
+    pub fn of(values: impl Iterator<Item = f32>) -> Summary {
+        let mut finite: Vec<f32> = values.filter(|v| v.is_finite()).collect();
+        if finite.is_empty() {
+            return Summary {
+                count: 0,
+                min: 0.,
+                max: 0.,
+                mean: 0.,
+                p1: 0.,
+                p5: 0.,
+                median: 0.,
+                p95: 0.,
+                p99: 0.,
+            };
+        }
+        finite.sort_by(|a, b| a.total_cmp(b));
+        // summed in f64, a grid easily has a million samples
+        let mean = finite.iter().map(|&v| v as f64).sum::<f64>() / finite.len() as f64;
+        Summary {
+            count: finite.len(),
+            min: finite[0],
+            max: finite[finite.len() - 1],
+            mean: mean as f32,
+            p1: percentile_sorted(&finite, 1.),
+            p5: percentile_sorted(&finite, 5.),
+            median: percentile_sorted(&finite, 50.),
+            p95: percentile_sorted(&finite, 95.),
+            p99: percentile_sorted(&finite, 99.),
+        }
+    }
+}
+
+// statistics of an evaluated grid, for fitting the camera and vertical scale
+// and for warning about undefined points
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotStats {
+    #[wasm_bindgen(readonly)]
+    pub re: Summary,
+    #[wasm_bindgen(readonly)]
+    pub im: Summary,
+    #[wasm_bindgen(readonly)]
+    pub norm: Summary,
+    // samples where f(z) has a NaN component
+    #[wasm_bindgen(readonly)]
+    pub nan: usize,
+    // samples where f(z) has an infinite (and no NaN) component
+    #[wasm_bindgen(readonly)]
+    pub infinite: usize,
+    #[wasm_bindgen(readonly)]
+    pub total: usize,
+}
+
+impl PlotStats {
+    pub fn of(samples: &[Complex32]) -> PlotStats {
+        let nan = samples.iter().filter(|s| s.is_nan()).count();
+        let infinite = samples.iter().filter(|s| s.is_infinite()).count();
+        PlotStats {
+            re: Summary::of(samples.iter().map(|s| s.re)),
+            im: Summary::of(samples.iter().map(|s| s.im)),
+            norm: Summary::of(samples.iter().map(|s| s.norm())),
+            nan,
+            infinite,
+            total: samples.len(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl PlotStats {
+    // NaN or infinite samples
+    #[wasm_bindgen(getter, js_name = nonFinite)]
+    pub fn non_finite(&self) -> usize {
+        self.nan + self.infinite
+    }
+}
+
+// the given percentile (0 - 100) of already sorted values, nearest rank
+pub fn percentile_sorted(sorted: &[f32], percentile: f32) -> f32 {
+    let k = (percentile / 100. * (sorted.len() - 1) as f32).round() as usize;
+    sorted[k.min(sorted.len() - 1)]
+}
+
+// the given percentile (0 - 100) of the finite values, 0 if there are none
+pub fn percentile(values: &[f32], percentile: f32) -> f32 {
+    let mut finite: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if finite.is_empty() {
+        return 0.;
+    }
+    let k =
+        ((percentile / 100. * (finite.len() - 1) as f32).round() as usize).min(finite.len() - 1);
+    let (_, p, _) = finite.select_nth_unstable_by(k, |a, b| a.total_cmp(b));
+    *p
+}
@@ -0,0 +1,448 @@
+use crate::colormap::ColorStyle;
+use crate::domain::Domain;
+use crate::plot::{grid_z, shade, sig, Plot};
+use crate::plot_result::VertexKind;
+use crate::png::{base64, encode_rgb};
+use crate::polyline::{xml_escape, Polylines};
+use num_complex::Complex32;
+use std::f32::consts::PI;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+// half the side of the plotted square of the input plane
+const EXTENT: f32 = 10.;
+// space around the plot area, in px
+const MARGIN_LEFT: f32 = 55.;
+const MARGIN_TOP: f32 = 15.;
+const MARGIN_BOTTOM: f32 = 45.;
+const MARGIN_RIGHT: f32 = 15.;
+// room taken by the colourbar and its labels
+const COLORBAR_WIDTH: f32 = 80.;
+
+// a 2D plot of an evaluated grid as an svg: the domain colouring as an
+// embedded PNG behind vector axes, contour lines, zero and pole markers and a
+// colourbar. everything is written by hand so it works the same natively and
+// in the browser
+#[wasm_bindgen]
+pub struct SvgExport {
+    // background, rgb bytes per sample, top row (largest im) first
+    cols: usize,
+    rows: usize,
+    pixels: Vec<u8>,
+    // samples per unit
+    n: i16,
+    domain: Domain,
+    // in grid coordinates, like PlotResult's positions
+    zeros: Vec<Complex32>,
+    poles: Vec<Complex32>,
+    // colours of the colourbar from bottom to top
+    bar: Vec<[f32; 3]>,
+    // (position from 0 at the bottom to 1 at the top, label)
+    bar_ticks: Vec<(f32, String)>,
+    bar_title: String,
+    // lines, stroke colour and stroke width in px
+    overlays: Vec<(Polylines, String, f32)>,
+    // frame, axes through 0, ticks and labels
+    pub axes: bool,
+    // a circle at every zero and a cross at every pole
+    pub markers: bool,
+    pub colorbar: bool,
+}
+
+impl SvgExport {
+    // the background is coloured like render(graph_type, ..., style), without
+    // alpha
+    pub fn from_plot(plot: &Plot, graph_type: u8, style: &ColorStyle) -> SvgExport {
+        let mut style = style.clone();
+        style.alpha = None;
+        let colors = plot.colors(graph_type, &style);
+        let (cols, rows) = (plot.cols, plot.rows);
+        let mut pixels = Vec::with_capacity(3 * cols * rows);
+        for row in 0..rows {
+            let j = rows - 1 - row;
+            for i in 0..cols {
+                let k = i * rows + j;
+                for c in &colors[3 * k..3 * k + 3] {
+                    pixels.push((c.clamp(0., 1.) * 255.).round() as u8);
+                }
+            }
+        }
+
+        // the colourbar covers the range of the hidden component
+        let (t0, t1, bar_ticks, bar_title) = match graph_type {
+            0..=3 => {
+                let ticks = [-20., -10., -5., 0., 5., 10., 20.]
+                    .iter()
+                    .map(|&v: &f32| (sig(v), format!("{}", v)))
+                    .collect();
+                let title = if graph_type < 2 { "Im f" } else { "Re f" };
+                (0., 1., ticks, title)
+            }
+            _ => {
+                let labels = ["-π", "-π/2", "0", "π/2", "π"];
+                let ticks = labels
+                    .iter()
+                    .enumerate()
+                    .map(|(k, label)| (k as f32 / 4., label.to_string()))
+                    .collect();
+                (-0.5, 0.5, ticks, "arg f")
+            }
+        };
+        let bar = (0..=64)
+            .map(|k| shade(t0 + (t1 - t0) * k as f32 / 64., graph_type, &style))
+            .collect();
+        let (zeros, poles) = zeros_and_poles(plot);
+        SvgExport {
+            cols,
+            rows,
+            pixels,
+            n: plot.n,
+            domain: plot.domain(),
+            zeros,
+            poles,
+            bar,
+            bar_ticks,
+            bar_title: bar_title.to_string(),
+            overlays: Vec::new(),
+            axes: true,
+            markers: true,
+            colorbar: true,
+        }
+    }
+
+    pub fn zeros(&self) -> &[Complex32] {
+        &self.zeros
+    }
+
+    pub fn poles(&self) -> &[Complex32] {
+        &self.poles
+    }
+}
+
+#[wasm_bindgen]
+impl SvgExport {
+    // the colormap arguments are the same as for evaluate_styled
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        plot: &Plot,
+        graph_type: u8,
+        colormap: &str,
+        saturation: f32,
+        value: f32,
+    ) -> Result<SvgExport, JsValue> {
+        let style = ColorStyle::parse(colormap, saturation, value, 1., "")
+            .map_err(|e| JsValue::from_str(&e))?;
+        Ok(SvgExport::from_plot(plot, graph_type, &style))
+    }
+
+    // drawn over the background in the order they are added. the lines are
+    // in the input plane, e.g. from Plot.contours
+    #[wasm_bindgen(js_name = addLines)]
+    pub fn add_lines(&mut self, lines: &Polylines, stroke: &str, stroke_width: f32) {
+        self.overlays
+            .push((lines.clone(), stroke.to_string(), stroke_width));
+    }
+
+    // the whole document, with a plot area of size x size px
+    #[wasm_bindgen(js_name = toSvg)]
+    pub fn to_svg(&self, size: u32) -> String {
+        let size = size as f32;
+        let right = MARGIN_RIGHT + if self.colorbar { COLORBAR_WIDTH } else { 0. };
+        let width = MARGIN_LEFT + size + right;
+        let height = MARGIN_TOP + size + MARGIN_BOTTOM;
+        // plane coordinates to px
+        let px = |z: Complex32| {
+            (
+                MARGIN_LEFT + (z.re + EXTENT) / (2. * EXTENT) * size,
+                MARGIN_TOP + (EXTENT - z.im) / (2. * EXTENT) * size,
+            )
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">",
+            width, height, width, height
+        );
+        let _ = writeln!(svg, "<defs>");
+        let _ = writeln!(
+            svg,
+            "<clipPath id=\"plot-area\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath>",
+            MARGIN_LEFT, MARGIN_TOP, size, size
+        );
+        if self.colorbar {
+            let _ = writeln!(
+                svg,
+                "<linearGradient id=\"colorbar\" x1=\"0\" y1=\"1\" x2=\"0\" y2=\"0\">"
+            );
+            for (k, c) in self.bar.iter().enumerate() {
+                let _ = writeln!(
+                    svg,
+                    "<stop offset=\"{:.4}\" stop-color=\"{}\"/>",
+                    k as f32 / (self.bar.len() - 1) as f32,
+                    hex(*c)
+                );
+            }
+            let _ = writeln!(svg, "</linearGradient>");
+        }
+        let _ = writeln!(svg, "</defs>");
+
+        // each pixel is centred on its sample, so the image reaches half a
+        // sample beyond the plotted square and gets clipped
+        let half = 0.5 / self.n as f32;
+        let (x0, y0) = px(Complex32::new(-EXTENT - half, EXTENT + half));
+        let (x1, y1) = px(Complex32::new(EXTENT + half, -EXTENT - half));
+        let png = encode_rgb(self.cols, self.rows, &self.pixels);
+        let _ = writeln!(
+            svg,
+            "<image x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" preserveAspectRatio=\"none\" clip-path=\"url(#plot-area)\" href=\"data:image/png;base64,{}\"/>",
+            x0,
+            y0,
+            x1 - x0,
+            y1 - y0,
+            base64(&png)
+        );
+
+        // Polylines::svg_path is in (x, -y), centred on 0. the clip is on a
+        // group since it would be transformed along with the path otherwise
+        let (cx, cy) = px(Complex32::new(0., 0.));
+        for (lines, stroke, stroke_width) in self.overlays.iter() {
+            let _ = writeln!(
+                svg,
+                "<g clip-path=\"url(#plot-area)\"><path d=\"{}\" transform=\"translate({} {}) scale({})\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"round\" vector-effect=\"non-scaling-stroke\"/></g>",
+                lines.svg_path(),
+                cx,
+                cy,
+                size / (2. * EXTENT),
+                xml_escape(stroke),
+                stroke_width
+            );
+        }
+
+        if self.markers {
+            for &z in self.zeros.iter() {
+                let (x, y) = px(z);
+                let _ = writeln!(
+                    svg,
+                    "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"4\" fill=\"white\" stroke=\"black\" stroke-width=\"1.5\"/>",
+                    x, y
+                );
+            }
+            for &z in self.poles.iter() {
+                let (x, y) = px(z);
+                let _ = writeln!(
+                    svg,
+                    "<path d=\"M-4 -4L4 4M-4 4L4 -4\" transform=\"translate({:.2} {:.2})\" stroke=\"black\" stroke-width=\"2\"/>",
+                    x, y
+                );
+            }
+        }
+
+        if self.axes {
+            self.write_axes(&mut svg, size, px);
+        }
+        if self.colorbar {
+            self.write_colorbar(&mut svg, size);
+        }
+        let _ = writeln!(svg, "</svg>");
+        svg
+    }
+}
+
+impl SvgExport {
+    fn write_axes(&self, svg: &mut String, size: f32, px: impl Fn(Complex32) -> (f32, f32)) {
+        let (cx, cy) = px(Complex32::new(0., 0.));
+        let (left, top) = (MARGIN_LEFT, MARGIN_TOP);
+        let (right, bottom) = (left + size, top + size);
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"black\" stroke-opacity=\"0.5\"><line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/><line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/></g>",
+            left, cy, right, cy, cx, top, cx, bottom
+        );
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>",
+            left, top, size, size
+        );
+        // on the sphere the grid coordinates aren't z, so only the frame
+        if self.domain != Domain::Plane {
+            return;
+        }
+        for k in -5..=5 {
+            let v = 2. * k as f32;
+            let (x, _) = px(Complex32::new(v, 0.));
+            let (_, y) = px(Complex32::new(0., v));
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{:.2}\" y1=\"{}\" x2=\"{:.2}\" y2=\"{}\" stroke=\"black\"/><text x=\"{:.2}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                x,
+                bottom,
+                x,
+                bottom + 5.,
+                x,
+                bottom + 18.,
+                v
+            );
+            let label = match k {
+                0 => "0".to_string(),
+                _ => format!("{}i", v),
+            };
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{:.2}\" x2=\"{}\" y2=\"{:.2}\" stroke=\"black\"/><text x=\"{}\" y=\"{:.2}\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>",
+                left - 5.,
+                y,
+                left,
+                y,
+                left - 8.,
+                y,
+                label
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">Re z</text>",
+            left + size / 2.,
+            bottom + 36.
+        );
+        let _ = writeln!(
+            svg,
+            "<text transform=\"translate({} {}) rotate(-90)\" text-anchor=\"middle\">Im z</text>",
+            left - 42.,
+            top + size / 2.
+        );
+    }
+
+    fn write_colorbar(&self, svg: &mut String, size: f32) {
+        let x = MARGIN_LEFT + size + 20.;
+        let (top, bar_width) = (MARGIN_TOP + 20., 15.);
+        let bar_height = size - 20.;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"url(#colorbar)\" stroke=\"black\"/>",
+            x, top, bar_width, bar_height
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            x + bar_width / 2.,
+            top - 8.,
+            self.bar_title
+        );
+        for (pos, label) in self.bar_ticks.iter() {
+            let y = top + (1. - pos) * bar_height;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{:.2}\" x2=\"{}\" y2=\"{:.2}\" stroke=\"black\"/><text x=\"{}\" y=\"{:.2}\" dominant-baseline=\"middle\">{}</text>",
+                x + bar_width,
+                y,
+                x + bar_width + 4.,
+                y,
+                x + bar_width + 7.,
+                y,
+                label
+            );
+        }
+    }
+}
+
+fn hex(c: [f32; 3]) -> String {
+    let byte = |v: f32| (v.clamp(0., 1.) * 255.).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", byte(c[0]), byte(c[1]), byte(c[2]))
+}
+
+// zeros and poles in grid coordinates: samples that are exactly 0 or
+// infinite, and cells the argument winds around (counter-clockwise +1 for a
+// zero, -1 for a pole, by the argument principle). cells touching such a
+// sample or an undefined one are skipped. a branch cut through a cell can
+// look like a winding too, so functions with cuts may get stray markers
+fn zeros_and_poles(plot: &Plot) -> (Vec<Complex32>, Vec<Complex32>) {
+    let (cols, rows) = (plot.cols, plot.rows);
+    let samples = plot.samples();
+    let (mut zeros, mut poles) = (Vec::new(), Vec::new());
+    let regular = |f: Complex32| f.is_finite() && f != Complex32::new(0., 0.);
+    for i in 0..cols {
+        for j in 0..rows {
+            let f = samples[i * rows + j];
+            if f == Complex32::new(0., 0.) {
+                zeros.push(grid_z(plot.n, i, j));
+            } else if VertexKind::of(f, 0.) == VertexKind::Pole {
+                poles.push(grid_z(plot.n, i, j));
+            }
+        }
+    }
+    for i in 0..cols.saturating_sub(1) {
+        for j in 0..rows.saturating_sub(1) {
+            let corners = [
+                samples[i * rows + j],
+                samples[(i + 1) * rows + j],
+                samples[(i + 1) * rows + j + 1],
+                samples[i * rows + j + 1],
+            ];
+            if !corners.iter().all(|&f| regular(f)) {
+                continue;
+            }
+            let mut turn = 0.;
+            for k in 0..4 {
+                let d = corners[(k + 1) % 4].arg() - corners[k].arg();
+                // the step of the argument, into [-π, π)
+                turn += (d + PI).rem_euclid(2. * PI) - PI;
+            }
+            // a multiple of 2π up to rounding
+            let winding = (turn / (2. * PI)).round();
+            let zs = [
+                grid_z(plot.n, i, j),
+                grid_z(plot.n, i + 1, j),
+                grid_z(plot.n, i + 1, j + 1),
+                grid_z(plot.n, i, j + 1),
+            ];
+            if winding > 0. {
+                zeros.push(locate(corners, zs));
+            } else if winding < 0. {
+                poles.push(locate(corners.map(|f| f.inv()), zs));
+            }
+        }
+    }
+    (zeros, poles)
+}
+
+// where the affine fit a z + b of g at the corners of a grid cell (counter-
+// clockwise from the lower left) vanishes, or the centre of the cell if that
+// is outside of it
+fn locate(g: [Complex32; 4], zs: [Complex32; 4]) -> Complex32 {
+    let centre = (zs[0] + zs[2]) / 2.;
+    let (dx, dy) = (zs[1] - zs[0], zs[3] - zs[0]);
+    let along_re = (g[1] - g[0] + g[2] - g[3]) / (dx * 2.);
+    let along_im = (g[3] - g[0] + g[2] - g[1]) / (dy * 2.);
+    let a = (along_re + along_im) / 2.;
+    let mean = (g[0] + g[1] + g[2] + g[3]) / 4.;
+    let z = centre - mean / a;
+    let inside = (zs[0].re..=zs[2].re).contains(&z.re) && (zs[0].im..=zs[2].im).contains(&z.im);
+    if inside {
+        z
+    } else {
+        centre
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colormap::Colormap;
+    use crate::parser::ShuntingParser;
+
+    #[test]
+    fn escapes_overlay_stroke() {
+        let expr = ShuntingParser::parse_str("z").unwrap();
+        let plot = Plot::sample(&expr, 1).unwrap();
+        let mut svg = SvgExport::from_plot(&plot, 4, &ColorStyle::new(Colormap::Hsv));
+        let mut lines = Polylines::new();
+        lines.push(&[[-1., 0., 0.], [1., 0., 0.]]);
+        svg.add_lines(&lines, "blue\"/><script>alert(1)</script><g a=\"", 1.);
+        let doc = svg.to_svg(200);
+        assert!(!doc.contains("<script>"));
+        assert!(doc.contains(
+            "stroke=\"blue&quot;/&gt;&lt;script&gt;alert(1)&lt;/script&gt;&lt;g a=&quot;\""
+        ));
+    }
+}
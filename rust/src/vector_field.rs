@@ -0,0 +1,301 @@
+use crate::height_map::HeightMap;
+use crate::parser::{RPNExpr, ShuntingParser};
+use crate::plot::Plot;
+use crate::polyline::Polylines;
+use crate::rpneval::MathContext;
+use js_sys::Float32Array;
+use num_complex::Complex32;
+use wasm_bindgen::prelude::*;
+
+// half the side of the plotted square of the input plane
+const EXTENT: f32 = 10.;
+// the finest glyph spacing, 401 x 401 glyphs over the square
+pub(crate) const MIN_SPACING: f32 = 0.05;
+// the shortest streamline step, near the edge of the square shorter steps are
+// lost to f32 rounding
+const MIN_STEP: f32 = 1e-4;
+// the most RK4 steps over all streamlines, 2 seeds^2 max_steps, each of them
+// evaluates f 4 times
+const MAX_TOTAL_STEPS: usize = 4_000_000;
+
+// the Pólya field conj(f(z)) of a function: arrow glyphs on a coarse grid and
+// streamlines, both in the input plane at height 0 (the coordinates of
+// PlotResult's positions) until lifted onto a surface with on_surface. the
+// work done along a curve is Re ∫ f dz and the flux through it Im ∫ f dz, so
+// the picture shows contour integrals directly
+#[wasm_bindgen]
+pub struct VectorField {
+    // (x, y, z, dx, dy, magnitude) per glyph, z is the height, (dx, dy) the
+    // unit direction (0, 0 where f vanishes)
+    glyphs: Vec<f32>,
+    streamlines: Polylines,
+}
+
+impl VectorField {
+    // glyphs every `spacing` (at least MIN_SPACING) units over
+    // [-10, 10] x [-10, 10], streamlines
+    // from `seeds` x `seeds` evenly spread starting points, traced both ways
+    // with RK4 steps of arc length `step` (at least MIN_STEP), at most
+    // max_steps each way and MAX_TOTAL_STEPS in all
+    pub fn polya(
+        expr: &RPNExpr,
+        spacing: f32,
+        seeds: usize,
+        step: f32,
+        max_steps: usize,
+    ) -> Result<VectorField, String> {
+        if spacing.is_nan() || spacing < MIN_SPACING {
+            return Err(format!(
+                "Bad glyph spacing: {}, it has to be at least {}",
+                spacing, MIN_SPACING
+            ));
+        }
+        if !step.is_finite() || step < MIN_STEP {
+            return Err(format!(
+                "Bad streamline step: {}, it has to be at least {}",
+                step, MIN_STEP
+            ));
+        }
+        let total = seeds
+            .checked_mul(seeds)
+            .and_then(|n| n.checked_mul(2 * max_steps.min(MAX_TOTAL_STEPS)))
+            .unwrap_or(usize::MAX);
+        if total > MAX_TOTAL_STEPS {
+            return Err(format!(
+                "Too many streamline steps: {} seeds^2 x {} steps each way, at most {} in all",
+                seeds, max_steps, MAX_TOTAL_STEPS
+            ));
+        }
+        let ctx = MathContext::new();
+
+        let mut glyphs = Vec::new();
+        let count = (2. * EXTENT / spacing).floor() as usize + 1;
+        for i in 0..count {
+            for j in 0..count {
+                let z = Complex32::new(-EXTENT + i as f32 * spacing, -EXTENT + j as f32 * spacing);
+                let v = ctx.eval(expr, z)?.conj();
+                let magnitude = v.norm();
+                if !magnitude.is_finite() {
+                    continue;
+                }
+                let dir = if magnitude > 0. { v / magnitude } else { v };
+                glyphs.extend_from_slice(&[z.re, z.im, 0., dir.re, dir.im, magnitude]);
+            }
+        }
+
+        let mut streamlines = Polylines::new();
+        for i in 0..seeds {
+            for j in 0..seeds {
+                let cell = 2. * EXTENT / seeds as f32;
+                let seed = Complex32::new(
+                    -EXTENT + (i as f32 + 0.5) * cell,
+                    -EXTENT + (j as f32 + 0.5) * cell,
+                );
+                let line = trace(&ctx, expr, seed, step, max_steps)?;
+                let line: Vec<[f32; 3]> = line.iter().map(|z| [z.re, z.im, 0.]).collect();
+                streamlines.push(&line);
+            }
+        }
+
+        Ok(VectorField {
+            glyphs,
+            streamlines,
+        })
+    }
+
+    pub fn glyphs(&self) -> &[f32] {
+        &self.glyphs
+    }
+
+    pub fn streamlines(&self) -> &Polylines {
+        &self.streamlines
+    }
+
+    // the glyphs and streamlines lifted onto the surface of
+    // plot.render(graph_type, height_map, ...), like Plot::contours_on
+    pub fn on_surface(&self, plot: &Plot, graph_type: u8, height_map: &HeightMap) -> VectorField {
+        let heights = plot.surface_heights(graph_type, height_map);
+        let mut glyphs = self.glyphs.clone();
+        for glyph in glyphs.chunks_mut(6) {
+            glyph[2] = plot.surface_height(&heights, Complex32::new(glyph[0], glyph[1]));
+        }
+        let mut streamlines = Polylines::new();
+        for mut line in self.streamlines.lines() {
+            for p in line.iter_mut() {
+                p[2] = plot.surface_height(&heights, Complex32::new(p[0], p[1]));
+            }
+            streamlines.push(&line);
+        }
+        VectorField {
+            glyphs,
+            streamlines,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl VectorField {
+    // the arguments are the same as for VectorField::polya
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        input: &str,
+        spacing: f32,
+        seeds: usize,
+        step: f32,
+        max_steps: usize,
+    ) -> Result<VectorField, JsValue> {
+        let expr = ShuntingParser::parse_str(input).map_err(|e| JsValue::from_str(&e))?;
+        VectorField::polya(&expr, spacing, seeds, step, max_steps)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    // a view into wasm memory, copy it before calling into wasm again
+    #[wasm_bindgen(getter, js_name = glyphs)]
+    pub fn glyphs_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(&self.glyphs) }
+    }
+
+    #[wasm_bindgen(getter, js_name = glyphCount)]
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len() / 6
+    }
+
+    #[wasm_bindgen(getter, js_name = streamlines)]
+    pub fn streamlines_js(&self) -> Polylines {
+        self.streamlines.clone()
+    }
+
+    // see HeightMap::parse for height_map
+    #[wasm_bindgen(js_name = onSurface)]
+    pub fn on_surface_js(
+        &self,
+        plot: &Plot,
+        graph_type: u8,
+        height_map: &str,
+    ) -> Result<VectorField, JsValue> {
+        let height_map = HeightMap::parse(height_map).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.on_surface(plot, graph_type, &height_map))
+    }
+}
+
+// the streamline through seed, backward part first so it runs along the field
+fn trace(
+    ctx: &MathContext,
+    expr: &RPNExpr,
+    seed: Complex32,
+    step: f32,
+    max_steps: usize,
+) -> Result<Vec<Complex32>, String> {
+    let (forward, closed) = integrate(ctx, expr, seed, step, max_steps)?;
+    if closed {
+        return Ok(forward);
+    }
+    let (mut line, _) = integrate(ctx, expr, seed, -step, max_steps)?;
+    line.reverse();
+    line.extend_from_slice(&forward[1..]);
+    Ok(line)
+}
+
+// follows the field from seed with steps of arc length h (backwards for
+// negative h) until it leaves the square, reaches a zero or a singularity,
+// runs out of steps or comes back to the seed. returns the points and
+// whether the line closed up
+fn integrate(
+    ctx: &MathContext,
+    expr: &RPNExpr,
+    seed: Complex32,
+    h: f32,
+    max_steps: usize,
+) -> Result<(Vec<Complex32>, bool), String> {
+    let mut line = vec![seed];
+    let mut z = seed;
+    for _ in 0..max_steps {
+        let next = rk4(ctx, expr, z, h)?;
+        if !next.is_finite() || next.re.abs() > EXTENT || next.im.abs() > EXTENT {
+            break;
+        }
+        // the directions cancel out near a sink or source, where normalized
+        // steps would just oscillate around it
+        if (next - z).norm() < h.abs() / 2. {
+            break;
+        }
+        line.push(next);
+        // a closed orbit (e.g. around a centre) is done after one turn
+        if line.len() > 8 && (next - seed).norm() < h.abs() {
+            line.push(seed);
+            return Ok((line, true));
+        }
+        z = next;
+    }
+    Ok((line, false))
+}
+
+// one classic Runge-Kutta step along the unit direction of the field
+fn rk4(ctx: &MathContext, expr: &RPNExpr, z: Complex32, h: f32) -> Result<Complex32, String> {
+    let k1 = direction(ctx, expr, z)?;
+    let k2 = direction(ctx, expr, z + k1 * (h / 2.))?;
+    let k3 = direction(ctx, expr, z + k2 * (h / 2.))?;
+    let k4 = direction(ctx, expr, z + k3 * h)?;
+    Ok(z + (k1 + k2 * 2. + k3 * 2. + k4) * (h / 6.))
+}
+
+// conj(f(z)) scaled to unit length, NaN at zeros and where f is undefined so
+// the step that hits one comes out NaN
+fn direction(ctx: &MathContext, expr: &RPNExpr, z: Complex32) -> Result<Complex32, String> {
+    let v = ctx.eval(expr, z)?.conj();
+    let len = v.norm();
+    if len.is_finite() && len > 1e-6 {
+        Ok(v / len)
+    } else {
+        Ok(Complex32::new(f32::NAN, f32::NAN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_fine_spacing() {
+        let expr = ShuntingParser::parse_str("z").unwrap();
+        for spacing in [0., 1e-6, 0.049, f32::NAN] {
+            assert!(VectorField::polya(&expr, spacing, 1, 0.1, 10).is_err());
+        }
+        let field = VectorField::polya(&expr, MIN_SPACING, 0, 0.1, 10).unwrap();
+        assert_eq!(field.glyph_count(), 401 * 401);
+    }
+
+    #[test]
+    fn bounds_the_streamlines() {
+        let expr = ShuntingParser::parse_str("z").unwrap();
+        for step in [0., -0.1, 1e-6, f32::NAN, f32::INFINITY] {
+            assert!(VectorField::polya(&expr, 1., 1, step, 10).is_err());
+        }
+        // the hang from new("z", 1, 100000, 1e-6, 1e9), with a valid step
+        assert!(VectorField::polya(&expr, 1., 100000, 0.1, 1_000_000_000).is_err());
+        assert!(VectorField::polya(&expr, 1., 1, 0.1, usize::MAX).is_err());
+        assert!(VectorField::polya(&expr, 1., usize::MAX, 0.1, 1).is_err());
+        assert!(VectorField::polya(&expr, 1., 20, 0.1, MAX_TOTAL_STEPS / 800 + 1).is_err());
+        let field = VectorField::polya(&expr, 1., 20, 0.1, MAX_TOTAL_STEPS / 800).unwrap();
+        assert_eq!(field.streamlines().len(), 400);
+    }
+
+    #[test]
+    fn lifts_onto_the_surface() {
+        // height = Re f = x on the Re-Im plot, which bilinear interpolation
+        // reproduces between the grid points
+        let expr = ShuntingParser::parse_str("z + 2").unwrap();
+        let field = VectorField::polya(&expr, 2.5, 3, 0.1, 50).unwrap();
+        assert!(field.glyphs().chunks(6).all(|g| g[2] == 0.));
+        let plot = Plot::sample(&expr, 2).unwrap();
+        let lifted = field.on_surface(&plot, 0, &HeightMap::Linear);
+        assert_eq!(lifted.glyphs().len(), field.glyphs().len());
+        for g in lifted.glyphs().chunks(6) {
+            assert!((g[2] - (g[0] + 2.)).abs() < 1e-4, "{:?}", g);
+        }
+        assert_eq!(lifted.streamlines().len(), field.streamlines().len());
+        for p in lifted.streamlines().points().chunks(3) {
+            assert!((p[2] - (p[0] + 2.)).abs() < 1e-4, "{:?}", p);
+        }
+    }
+}
@@ -0,0 +1,647 @@
+use crate::elliptic::{ellip_k, scaled_sin_cos, theta_all, theta_scaled};
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+const EPS: f64 = 1e-17;
+
+fn infinity() -> Complex64 {
+    Complex64::new(f64::INFINITY, 0.)
+}
+
+fn nan() -> Complex64 {
+    Complex64::new(f64::NAN, f64::NAN)
+}
+
+// --------------------------------------------------------------
+// lattices
+// --------------------------------------------------------------
+// a basis of the lattice Λ = w1 Z + w2 Z as Λ = ω (Z + τZ) with τ in the
+// fundamental domain |Re τ| <= 1/2, |τ| >= 1, so Im τ >= √3/2 and the theta
+// series need only a handful of terms (Lagrange's reduction). None for a
+// degenerate lattice
+fn reduce(w1: Complex64, w2: Complex64) -> Option<(Complex64, Complex64)> {
+    let (mut w1, mut w2) = (w1, w2);
+    for _ in 0..100 {
+        let tau = w2 / w1;
+        if !tau.is_finite() || tau.im == 0. {
+            return None;
+        }
+        if tau.im < 0. {
+            w2 = -w2;
+        }
+        w2 -= (w2 / w1).re.round() * w1;
+        if w2.norm() < w1.norm() {
+            std::mem::swap(&mut w1, &mut w2);
+        } else {
+            break;
+        }
+    }
+    let tau = w2 / w1;
+    if tau.im < 0. {
+        Some((w1, -tau))
+    } else {
+        Some((w1, tau))
+    }
+}
+
+// the periods 2K/s and 2iK'/s for s = √(e1 - e3) of the lattice with the
+// invariants g2 and g3, since ℘(z) = e3 + (e1 - e3) ns²(sz | m) for
+// m = (e2 - e3) / (e1 - e3) (DLMF 23.6.16) and the roots e of
+// 4t³ - g2 t - g3. labelling them so |e1 - e3| is largest keeps m within
+// |m| <= 1, |1 - m| <= 1, away from the cuts of K and K'
+fn periods(g2: Complex64, g3: Complex64) -> (Complex64, Complex64) {
+    let e = cubic_roots(g2, g3);
+    let (mut i1, mut i3) = (0, 1);
+    for (a, b) in [(0, 2), (1, 2)] {
+        if (e[a] - e[b]).norm() > (e[i1] - e[i3]).norm() {
+            (i1, i3) = (a, b);
+        }
+    }
+    let e2 = e[3 - i1 - i3];
+    let m = (e2 - e[i3]) / (e[i1] - e[i3]);
+    let s = (e[i1] - e[i3]).sqrt();
+    (
+        2. * ellip_k(m) / s,
+        2. * Complex64::i() * ellip_k(1. - m) / s,
+    )
+}
+
+// the roots of 4t³ - g2 t - g3 by Cardano's formula, t = u - p / 3u with
+// u³ = -q/2 + √(q²/4 + p³/27) for t³ + pt + q, polished by Newton's method
+fn cubic_roots(g2: Complex64, g3: Complex64) -> [Complex64; 3] {
+    let (p, q) = (-g2 / 4., -g3 / 4.);
+    let d = (q * q / 4. + p * p * p / 27.).sqrt();
+    // the larger of the two choices of u³
+    let u3 = if (-q / 2. + d).norm() >= (-q / 2. - d).norm() {
+        -q / 2. + d
+    } else {
+        -q / 2. - d
+    };
+    let u = u3.powf(1. / 3.);
+    let omega = Complex64::new(-0.5, 0.8660254037844386);
+    let mut roots = [u, omega * u, omega.conj() * u];
+    for t in roots.iter_mut() {
+        *t -= p / (3. * *t);
+        for _ in 0..2 {
+            let f = (*t * *t + p) * *t + q;
+            let df = 3. * *t * *t + p;
+            if df != Complex64::new(0., 0.) {
+                *t -= f / df;
+            }
+        }
+    }
+    roots
+}
+
+// --------------------------------------------------------------
+// weierstrass functions
+// --------------------------------------------------------------
+// [℘, ℘', ζ, σ] on the lattice w1 Z + w2 Z. with Λ = ω (Z + τZ) they're
+// ℘(z) = ω^-2 ℘(z/ω), ℘' = ω^-3 ℘', ζ = ω^-1 ζ, σ = ω σ on Z + τZ, where
+// 2ω1 = 1, 2ω3 = τ and for v = πz (DLMF 23.6.5, 23.6.8, 23.6.9)
+//   ℘(z) = π² (θ2 θ3 θ4(v) / θ1(v))² - π²/3 (θ2⁴ + θ3⁴),
+//   ℘'(z) = -2π³ (θ2 θ3 θ4)² θ2(v) θ3(v) θ4(v) / θ1(v)³,
+//   ζ(z) = 2η1 z + π θ1'(v) / θ1(v), σ(z) = e^(η1 z²) θ1(v) / (π θ1'),
+// with the thetas at 0 where no v is given, θ1' = θ2 θ3 θ4 and
+// η1 = -π²/6 θ1'''/θ1'. z is first reduced by 2w = a + bτ with
+// ζ(z + 2w) = ζ(z) + 2η, σ(z + 2w) = (-1)^(a+b+ab) e^(2η(z + w)) σ(z) for
+// η = aη1 + bη3 and Legendre's relation η3 = τη1 - iπ. θ1, θ2 and θ1' are
+// taken without their factor q^(1/4), and at v also without e^|Im v|,
+// which cancel or go into the exponents, so a long thin lattice (with a
+// large Im τ) doesn't overflow
+fn weierstrass(z: Complex64, w1: Complex64, w2: Complex64) -> [Complex64; 4] {
+    let (omega, tau) = match reduce(w1, w2) {
+        Some(basis) => basis,
+        None => return [nan(); 4],
+    };
+    let u = z / omega;
+    let b = (u.im / tau.im).round();
+    let a = (u - b * tau).re.round();
+    let u = u - a - b * tau;
+    if u == Complex64::new(0., 0.) {
+        let sigma = Complex64::new(0., 0.);
+        return [infinity(), infinity(), infinity(), sigma];
+    }
+    let i = Complex64::i();
+    let v = PI * u;
+    // ln q^(-1/4)
+    let quarter = -i * PI * tau / 4.;
+    let big = v.im.abs();
+    let [t1, t2, _, _] = theta_scaled(v, tau, quarter - big);
+    let [_, _, t3, t4] = theta_all(v, tau);
+    let [_, s2, _, _] = theta_scaled(Complex64::new(0., 0.), tau, quarter);
+    let [_, _, s3, s4] = theta_all(Complex64::new(0., 0.), tau);
+    let s1 = s2 * s3 * s4;
+    let (prime, eta1) = theta1_derivatives(v, tau, big);
+    let pi2 = PI * PI;
+    let r = s2 * s3 * t4 / t1 * (-big).exp();
+    let p = pi2 * r * r - pi2 / 3. * (s2.powi(4) * (-4. * quarter).exp() + s3.powi(4));
+    let dp = -2. * PI * pi2 * s1 * s1 * t2 * t3 * t4 / t1.powi(3) * (-2. * big).exp();
+    let mut zeta = 2. * eta1 * u + PI * prime / t1;
+    let mut exponent = eta1 * u * u + big;
+    if a != 0. || b != 0. {
+        let eta = a * eta1 + b * (tau * eta1 - i * PI);
+        let w = (a + b * tau) / 2.;
+        zeta += 2. * eta;
+        exponent += 2. * eta * (u + w);
+    }
+    let mut sigma = exponent.exp() * t1 / (PI * s1);
+    if (a + b + a * b) % 2. != 0. {
+        sigma = -sigma;
+    }
+    let omega2 = omega * omega;
+    [
+        p / omega2,
+        dp / (omega2 * omega),
+        zeta / omega,
+        sigma * omega,
+    ]
+    .map(finish)
+}
+
+// e^-c θ1'(v | τ) without q^(1/4), that's
+// 2 e^-c Σ (-1)^n (2n + 1) q^(n(n+1)) cos((2n + 1) v), and
+// η1 = -π²/6 θ1'''(0) / θ1'(0), where θ1'''(0) has -(2n + 1)³ in place of
+// (2n + 1) and no cosine
+fn theta1_derivatives(v: Complex64, tau: Complex64, c: f64) -> (Complex64, Complex64) {
+    let mut prime = Complex64::new(0., 0.);
+    let (mut first, mut third) = (Complex64::new(0., 0.), Complex64::new(0., 0.));
+    let mut sign = 1.;
+    for n in 0..40 {
+        let k = 2. * n as f64 + 1.;
+        let a = Complex64::i() * PI * tau * (k * k - 1.) / 4.;
+        let (_, cos) = scaled_sin_cos(a - c, k * v);
+        let term = sign * a.exp();
+        prime += sign * k * cos;
+        first += k * term;
+        third += k * k * k * term;
+        sign = -sign;
+        if (k * k * k * term).norm() + (k * cos).norm() < EPS {
+            break;
+        }
+    }
+    (prime, PI * PI / 6. * third / first)
+}
+
+// with Δ = g2³ - 27 g3² = 0 the lattice degenerates and for the double root
+// e = -3 g3 / 2 g2 and a = √(3e) (DLMF 23.5.iii)
+//   ℘(z) = e + a² / sinh²(az), ζ(z) = -ez + a coth(az),
+//   σ(z) = e^(-ez²/2) sinh(az) / a,
+// which are 1/z², 1/z and z when g2 = g3 = 0
+fn degenerate(z: Complex64, g2: Complex64, g3: Complex64) -> [Complex64; 4] {
+    if z == Complex64::new(0., 0.) {
+        return [infinity(), infinity(), infinity(), z];
+    }
+    if g2 == Complex64::new(0., 0.) {
+        return [1. / (z * z), -2. / (z * z * z), 1. / z, z];
+    }
+    let e = -1.5 * g3 / g2;
+    let a = (3. * e).sqrt();
+    let (s, c) = ((a * z).sinh(), (a * z).cosh());
+    [
+        e + a * a / (s * s),
+        -2. * a * a * a * c / (s * s * s),
+        -e * z + a * c / s,
+        (-e * z * z / 2.).exp() * s / a,
+    ]
+    .map(finish)
+}
+
+// the poles and overflow
+fn finish(w: Complex64) -> Complex64 {
+    if w.is_finite() {
+        w
+    } else {
+        infinity()
+    }
+}
+
+// on the lattice with the invariants g2 and g3, real for real z, g2 and g3
+fn invariants_checked(index: usize, z: Complex64, g2: Complex64, g3: Complex64) -> Complex64 {
+    if z.is_nan() || g2.is_nan() || g3.is_nan() {
+        return nan();
+    }
+    let w = if g2 * g2 * g2 == 27. * g3 * g3 {
+        degenerate(z, g2, g3)
+    } else {
+        let (w1, w2) = periods(g2, g3);
+        weierstrass(z, w1, w2)
+    }[index];
+    if z.im == 0. && g2.im == 0. && g3.im == 0. && w.is_finite() {
+        Complex64::new(w.re, 0.)
+    } else {
+        w
+    }
+}
+
+// on the lattice Z + τZ, NaN for real τ
+fn lattice_checked(index: usize, z: Complex64, tau: Complex64) -> Complex64 {
+    if z.is_nan() || tau.is_nan() {
+        return nan();
+    }
+    weierstrass(z, Complex64::new(1., 0.), tau)[index]
+}
+
+pub fn wp(z: Complex64, g2: Complex64, g3: Complex64) -> Complex64 {
+    invariants_checked(0, z, g2, g3)
+}
+
+pub fn wp_prime(z: Complex64, g2: Complex64, g3: Complex64) -> Complex64 {
+    invariants_checked(1, z, g2, g3)
+}
+
+pub fn wzeta(z: Complex64, g2: Complex64, g3: Complex64) -> Complex64 {
+    invariants_checked(2, z, g2, g3)
+}
+
+pub fn wsigma(z: Complex64, g2: Complex64, g3: Complex64) -> Complex64 {
+    invariants_checked(3, z, g2, g3)
+}
+
+pub fn wp_lattice(z: Complex64, tau: Complex64) -> Complex64 {
+    lattice_checked(0, z, tau)
+}
+
+pub fn wp_prime_lattice(z: Complex64, tau: Complex64) -> Complex64 {
+    lattice_checked(1, z, tau)
+}
+
+pub fn wzeta_lattice(z: Complex64, tau: Complex64) -> Complex64 {
+    lattice_checked(2, z, tau)
+}
+
+pub fn wsigma_lattice(z: Complex64, tau: Complex64) -> Complex64 {
+    lattice_checked(3, z, tau)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    fn close(got: Complex64, want: Complex64) {
+        assert!(
+            (got - want).norm() <= 1e-12 * want.norm(),
+            "got {}, want {}",
+            got,
+            want
+        );
+    }
+
+    // ℘, ℘', ζ and σ from mpmath's theta functions at 40 digits, on the
+    // lemniscatic, a real, two complex and a degenerate lattice
+    #[test]
+    fn invariant_values() {
+        for (z, g2, g3, want) in [
+            (
+                c(0.3, 0.2),
+                c(1., 0.),
+                c(0., 0.),
+                [
+                    c(2.961078186042386, -7.0945924060696814),
+                    c(8.222960595126974, 41.89529057331295),
+                    c(2.3078423606489253, -1.53922812710583),
+                    c(0.3000248746502795, 0.19999491720088283),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(1., 0.),
+                c(0., 0.),
+                [
+                    c(0.2966460533212581, 0.36732015053444683),
+                    c(-0.10741180603983949, 0.8270802032881068),
+                    c(-0.62335296030955, -0.40674171265736486),
+                    c(-1.2189047701311437, 0.6897591824051226),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(1., 0.),
+                c(0., 0.),
+                [
+                    c(0.03666014682002847, -0.055795132162677646),
+                    c(-0.1212641048305315, -0.22921075223586232),
+                    c(0.41216383965968667, 0.4226536209026052),
+                    c(2.5040099984058455, -1.336350079830011),
+                ],
+            ),
+            (
+                c(0.3, 0.2),
+                c(4., 0.),
+                c(1., 0.),
+                [
+                    c(2.9681277613244914, -7.07617474801428),
+                    c(8.31122049421147, 41.96194285837694),
+                    c(2.30833578515924, -1.5415356525092516),
+                    c(0.30010002402360464, 0.19998045544783674),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(4., 0.),
+                c(1., 0.),
+                [
+                    c(0.28000608720784487, 0.03665670364705086),
+                    c(-0.039342017929286904, 1.4276811244703402),
+                    c(-0.6707186100602892, -0.5446495441261134),
+                    c(-1.2856099927962308, 0.6667672514700504),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(4., 0.),
+                c(1., 0.),
+                [
+                    c(-0.7192943997060479, -0.06195006131045771),
+                    c(-0.6575495275304637, 0.10331747439916023),
+                    c(1.0447756460270532, 0.5405747054633423),
+                    c(3.934237596105512, -2.047072849524291),
+                ],
+            ),
+            (
+                c(0.3, 0.2),
+                c(2., 3.),
+                c(1., -1.),
+                [
+                    c(2.9456003332615914, -7.080255741152064),
+                    c(8.198345810311494, 42.012738612361886),
+                    c(2.310325015566501, -1.5395959630023277),
+                    c(0.3000663051902685, 0.20006470204928997),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(2., 3.),
+                c(1., -1.),
+                [
+                    c(0.42631162850743964, 0.32929563074315216),
+                    c(-0.1697354593598886, 1.0669398312515777),
+                    c(-0.5426814023917105, -0.44981448006225955),
+                    c(-1.2119691102557446, 0.6384276010252129),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(2., 3.),
+                c(1., -1.),
+                [
+                    c(-0.8084322036661084, -0.44748645239738194),
+                    c(-0.5370503103396418, -1.0885112292003822),
+                    c(0.7010099658254425, 0.49042296068072083),
+                    c(2.7533696072774774, -1.1052712020632327),
+                ],
+            ),
+            (
+                c(0.3, 0.2),
+                c(-4., 0.),
+                c(2., 0.),
+                [
+                    c(2.947702757629318, -7.12374449955687),
+                    c(8.069955297208, 41.80854538414883),
+                    c(2.307178465268331, -1.5354110893017678),
+                    c(0.29990155373756644, 0.20002190236543432),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(-4., 0.),
+                c(2., 0.),
+                [
+                    c(-0.13436689325355305, 0.5148988023178552),
+                    c(0.525012718161414, 1.5476847721709366),
+                    c(-0.6999025001006797, -0.21818084313148178),
+                    c(-1.144601822801154, 0.7558113830188673),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(-4., 0.),
+                c(2., 0.),
+                [
+                    c(0.07665911826220136, -1.6710402085521139),
+                    c(2.0425724896619957, 2.9038588686234617),
+                    c(1.6556937137915715, 0.2838033722669767),
+                    c(1.1571471982822699, -3.357298132637219),
+                ],
+            ),
+            (
+                c(0.3, 0.2),
+                c(12., 0.),
+                c(8., 0.),
+                [
+                    c(2.984936183652427, -7.02527608782066),
+                    c(8.538250705692052, 42.16847445451494),
+                    c(2.3098407634951745, -1.5477195744610859),
+                    c(0.30030268621339756, 0.19994531872535812),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(12., 0.),
+                c(8., 0.),
+                [
+                    c(-0.37958588620442285, -0.7382467224424965),
+                    c(2.0107572445412223, 2.285685042751252),
+                    c(-0.9634762019226647, -0.8606182727564582),
+                    c(-1.505634680940177, 0.6437645671640978),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(12., 0.),
+                c(8., 0.),
+                [
+                    c(-1.0508387424555419, 0.07998165036985205),
+                    c(0.2817540631310464, 0.17394963170248118),
+                    c(2.122893565595311, 0.34681506113303506),
+                    c(7.156504279382342, -8.429775662427733),
+                ],
+            ),
+        ] {
+            close(wp(z, g2, g3), want[0]);
+            close(wp_prime(z, g2, g3), want[1]);
+            close(wzeta(z, g2, g3), want[2]);
+            close(wsigma(z, g2, g3), want[3]);
+        }
+    }
+
+    // the same on Z + τZ, τ = 2.7 + 0.05i far from the fundamental domain
+    #[test]
+    fn lattice_values() {
+        for (z, tau, want) in [
+            (
+                c(0.3, 0.2),
+                c(0., 1.),
+                [
+                    c(3.3721036737358197, -5.991418600455643),
+                    c(12.822790453615713, 45.83888817832227),
+                    c(2.337895521957628, -1.68063825000079),
+                    c(0.3046906853087618, 0.19905799361147397),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(0., 1.),
+                [
+                    c(-3.3721036737358228, -5.991418600455641),
+                    c(-45.83888817832227, -12.822790453615687),
+                    c(-4.822230903590583, -0.803697131632165),
+                    c(-5.149889327984047, 3.3644830195935334),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(0., 1.),
+                [
+                    c(-6.618126095726432, 1.8624142969203366),
+                    c(23.146837947208475, 12.980268383137345),
+                    c(7.028155101466342, 5.3321221929533955),
+                    c(6355.614584014242, -2707.058984295528),
+                ],
+            ),
+            (
+                c(0.3, 0.2),
+                c(0.3, 1.1),
+                [
+                    c(2.979821037913146, -6.135116447167418),
+                    c(11.129881041889627, 47.27286699835808),
+                    c(2.361290431131435, -1.6330357946849179),
+                    c(0.30340720162927254, 0.20031416523051201),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(0.3, 1.1),
+                [
+                    c(-0.7193852231835083, 0.5460884862892993),
+                    c(-2.966349908974136, -15.431735804186046),
+                    c(-3.9984874443067686, -1.2257526898083595),
+                    c(-5.753098496640338, 3.9929095492466975),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(0.3, 1.1),
+                [
+                    c(0.9762860203067465, 2.4608984890261922),
+                    c(5.121353665411031, -21.707599273169496),
+                    c(7.259116050487514, 3.9190310706819718),
+                    c(2663.4669102512335, 3595.5575768278954),
+                ],
+            ),
+            (
+                c(0.13, 0.02),
+                c(2.7, 0.05),
+                [
+                    c(28.14235467211739, 57.01262086266078),
+                    c(-223.09677570623566, 1259.3759244321209),
+                    c(10.314869520312959, -3.899824168908347),
+                    c(0.14802788363177122, 0.01330556157842223),
+                ],
+            ),
+            (
+                c(-0.61, 0.33),
+                c(2.7, 0.05),
+                [
+                    c(32.656631533586065, 105.89743496181656),
+                    c(134.21724369531015, 470.92680069164226),
+                    c(-18.214084545534874, -4.271444549645732),
+                    c(-38.94120431138592, -1.9635731310783904),
+                ],
+            ),
+            (
+                c(1.07, -0.21),
+                c(2.7, 0.05),
+                [
+                    c(39.24817424058973, 60.266931383845424),
+                    c(412.5439435888017, -958.0960217761321),
+                    c(40.675525486595156, -23.412800529338288),
+                    c(-116811737.70523301, -82856318.93054864),
+                ],
+            ),
+            (
+                c(0.3, 0.2),
+                c(-0.4, 0.3),
+                [
+                    c(3.587347529793938, -10.916812987131513),
+                    c(53.47389579258819, -0.9173413609290917),
+                    c(2.3490559724169375, -0.8225761869146362),
+                    c(0.27516100860899634, 0.21691305997356108),
+                ],
+            ),
+            (
+                c(-1.2, 0.7),
+                c(-0.4, 0.3),
+                [
+                    c(-22.87104869539492, -3.037138952749878),
+                    c(36.323118398208535, 263.24982685947737),
+                    c(-15.681714086692562, -6.9194997120260044),
+                    c(7506.336531657292, -55867.28171692128),
+                ],
+            ),
+            (
+                c(2.1, -1.4),
+                c(-0.4, 0.3),
+                [
+                    c(-0.9401785875290001, -51.25565911723794),
+                    c(482.07631508717213, 497.371331795675),
+                    c(31.431634066181786, 15.218340547944175),
+                    c(-2.528909986742323e17, 1.482619711538862e17),
+                ],
+            ),
+        ] {
+            close(wp_lattice(z, tau), want[0]);
+            close(wp_prime_lattice(z, tau), want[1]);
+            close(wzeta_lattice(z, tau), want[2]);
+            close(wsigma_lattice(z, tau), want[3]);
+        }
+    }
+
+    // (℘')² = 4℘³ - g2 ℘ - g3
+    #[test]
+    fn differential_equation() {
+        for (g2, g3) in [
+            (c(1., 0.), c(0., 0.)),
+            (c(4., 0.), c(1., 0.)),
+            (c(2., 3.), c(1., -1.)),
+            (c(0., 0.), c(1., 0.)),
+            (c(12., 0.), c(8., 0.)),
+        ] {
+            for z in [c(0.3, 0.2), c(-1.2, 0.7), c(2.1, -1.4), c(0.9, 0.)] {
+                let (p, dp) = (wp(z, g2, g3), wp_prime(z, g2, g3));
+                let rhs = 4. * p * p * p - g2 * p - g3;
+                assert!(
+                    (dp * dp - rhs).norm() <= 1e-12 * (4. * p * p * p).norm(),
+                    "at {} for g2 = {}, g3 = {}: {} against {}",
+                    z,
+                    g2,
+                    g3,
+                    dp * dp,
+                    rhs
+                );
+            }
+        }
+    }
+
+    // ℘ and ℘' have the periods 1 and τ, ζ gains a constant 2η across each
+    #[test]
+    fn periodicity() {
+        for tau in [c(0., 1.), c(0.3, 1.1), c(2.7, 0.05), c(-0.4, 0.3)] {
+            let steps = [
+                wzeta_lattice(c(0.13, 0.02) + 1., tau) - wzeta_lattice(c(0.13, 0.02), tau),
+                wzeta_lattice(c(0.13, 0.02) + tau, tau) - wzeta_lattice(c(0.13, 0.02), tau),
+            ];
+            for z in [c(0.13, 0.02), c(-0.61, 0.33), c(1.07, -0.21)] {
+                for (k, w) in [c(1., 0.), tau].into_iter().enumerate() {
+                    close(wp_lattice(z + w, tau), wp_lattice(z, tau));
+                    close(wp_prime_lattice(z + w, tau), wp_prime_lattice(z, tau));
+                    close(wzeta_lattice(z + w, tau) - wzeta_lattice(z, tau), steps[k]);
+                }
+            }
+        }
+    }
+}